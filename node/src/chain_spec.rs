@@ -1,15 +1,28 @@
+use codec::{Decode, Encode};
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY,
+	opaque::SessionKeys, token, AccountId, AuraConfig, Balance, BalancesConfig, BlockNumber,
+	CouncilConfig, ExistentialDeposit, GenesisConfig, GrandpaConfig, Hash, Header, ImOnlineConfig,
+	Index, SessionConfig, Signature, SystemConfig, TemplateModuleConfig, ValidatorSetConfig,
+	VestingConfig, WASM_BINARY,
 };
-use sc_service::ChainType;
+use pallet_im_online::sr25519::AuthorityId as ImOnlineId;
+use sc_service::{config::MultiaddrWithPeerId, ChainType, Properties};
+use sc_telemetry::TelemetryEndpoints;
+use serde::Deserialize;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_core::{sr25519, Pair, Public};
+use sp_core::{sr25519, twox_128, Pair, Public};
 use sp_finality_grandpa::AuthorityId as GrandpaId;
-use sp_runtime::traits::{IdentifyAccount, Verify};
-
-// The URL for the telemetry server.
-// const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
+use sp_runtime::{
+	traits::{BlakeTwo256, Hash as HashT, Header as HeaderT, IdentifyAccount, Verify},
+	BuildStorage,
+};
+use std::{
+	borrow::Cow,
+	collections::{BTreeMap, BTreeSet},
+	fs::{self, File},
+	io::BufReader,
+	path::Path,
+};
 
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
@@ -31,12 +44,242 @@ where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
-/// Generate an Aura authority key.
-pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
-	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
+/// Generate a validator account and its Aura, GRANDPA and ImOnline authority keys.
+pub fn authority_keys_from_seed(s: &str) -> (AccountId, AuraId, GrandpaId, ImOnlineId) {
+	(
+		get_account_id_from_seed::<sr25519::Public>(s),
+		get_from_seed::<AuraId>(s),
+		get_from_seed::<GrandpaId>(s),
+		get_from_seed::<ImOnlineId>(s),
+	)
+}
+
+fn session_keys(aura: AuraId, grandpa: GrandpaId, im_online: ImOnlineId) -> SessionKeys {
+	SessionKeys { aura, grandpa, im_online }
+}
+
+/// The chain spec properties with which wallets present the native token and addresses of the
+/// runtime.
+pub fn properties() -> Properties {
+	let mut properties = Properties::new();
+	properties.insert("tokenSymbol".into(), token::SYMBOL.into());
+	properties.insert("tokenDecimals".into(), token::DECIMALS.into());
+	properties.insert("ss58Format".into(), token::SS58_FORMAT.into());
+	properties
+}
+
+/// Check that chain spec `properties` present the native token and addresses as the runtime does.
+pub fn check_properties(properties: &Properties) -> Result<(), String> {
+	for (key, expected) in self::properties() {
+		match properties.get(&key) {
+			Some(value) if *value == expected => {},
+			Some(value) =>
+				return Err(format!(
+					"Chain spec property `{}` is {}, but the runtime uses {}",
+					key, value, expected
+				)),
+			None =>
+				return Err(format!(
+					"Chain spec property `{}` is missing, the runtime uses {}",
+					key, expected
+				)),
+		}
+	}
+	Ok(())
+}
+
+/// A vesting schedule of the genesis block.
+///
+/// `amount` is credited to `account` on top of any endowment and unlocks linearly over `period`
+/// blocks from block `start` on.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VestingEntry {
+	pub account: AccountId,
+	pub amount: Balance,
+	pub start: BlockNumber,
+	pub period: BlockNumber,
+}
+
+/// Read genesis vesting schedules from a JSON file holding an array of [`VestingEntry`] objects,
+/// with accounts in SS58 format.
+pub fn vesting_from_json_file(path: &Path) -> Result<Vec<VestingEntry>, String> {
+	let file = File::open(path)
+		.map_err(|e| format!("Error opening vesting file {}: {}", path.display(), e))?;
+	let entries: Vec<VestingEntry> = serde_json::from_reader(BufReader::new(file))
+		.map_err(|e| format!("Error parsing vesting file {}: {}", path.display(), e))?;
+
+	check_vesting(&entries)?;
+	Ok(entries)
+}
+
+fn check_vesting(entries: &[VestingEntry]) -> Result<(), String> {
+	let mut accounts = BTreeSet::new();
+	for entry in entries {
+		if !accounts.insert(&entry.account) {
+			return Err(format!("Vesting of {} is given more than once", entry.account))
+		}
+		// `pallet_balances` refuses accounts below the existential deposit at genesis.
+		if entry.amount < ExistentialDeposit::get() {
+			return Err(format!(
+				"Vesting of {} is below the existential deposit of {}",
+				entry.account,
+				ExistentialDeposit::get()
+			))
+		}
+		// `pallet_vesting` rejects schedules that unlock nothing per block.
+		if entry.period == 0 || entry.amount < Balance::from(entry.period) {
+			return Err(format!(
+				"Vesting of {} needs a non-zero period of at most `amount` blocks",
+				entry.account
+			))
+		}
+	}
+	Ok(())
+}
+
+/// The public keys of a validator, in SS58 format.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AuthorityKeys {
+	pub account: AccountId,
+	pub aura: AuraId,
+	pub grandpa: GrandpaId,
+	pub im_online: ImOnlineId,
+}
+
+/// A balance credited at genesis.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Endowment {
+	pub account: AccountId,
+	pub amount: Balance,
+}
+
+/// Description of a chain, from which the `generate-spec` subcommand builds its chain spec.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ChainDescription {
+	pub name: String,
+	pub id: String,
+	pub chain_type: ChainType,
+	/// The initial validators.
+	pub authorities: Vec<AuthorityKeys>,
+	/// The initial council, which holds the privileges of governance.
+	pub council: Vec<AccountId>,
+	#[serde(default)]
+	pub endowments: Vec<Endowment>,
+	#[serde(default)]
+	pub vesting: Vec<VestingEntry>,
+	#[serde(default)]
+	pub boot_nodes: Vec<MultiaddrWithPeerId>,
+	/// Telemetry servers as `(URL, verbosity)` pairs.
+	#[serde(default)]
+	pub telemetry_endpoints: Vec<(String, u8)>,
+	pub protocol_id: Option<String>,
+	/// Added to the token and address properties of the runtime, which must not be contradicted.
+	pub properties: Option<Properties>,
+}
+
+impl ChainDescription {
+	/// Read a chain description from a TOML file if `path` ends in `.toml`, and from a JSON file
+	/// otherwise.
+	pub fn from_file(path: &Path) -> Result<Self, String> {
+		let content = fs::read_to_string(path)
+			.map_err(|e| format!("Error opening chain description {}: {}", path.display(), e))?;
+		let description: Self = if path.extension().map_or(false, |ext| ext == "toml") {
+			toml::from_str(&content).map_err(|e| e.to_string())
+		} else {
+			serde_json::from_str(&content).map_err(|e| e.to_string())
+		}
+		.map_err(|e| format!("Error parsing chain description {}: {}", path.display(), e))?;
+
+		description.check()
+	}
+
+	fn check(self) -> Result<Self, String> {
+		if self.authorities.is_empty() {
+			return Err("A chain needs at least one authority".into())
+		}
+		if self.council.is_empty() {
+			return Err("A chain needs at least one council member".into())
+		}
+		check_vesting(&self.vesting)?;
+		Ok(self)
+	}
+}
+
+/// Build the chain spec of a chain described by `description`.
+pub fn custom_config(description: ChainDescription) -> Result<ChainSpec, String> {
+	let wasm_binary = WASM_BINARY.ok_or_else(|| "Wasm binary not available".to_string())?;
+	let ChainDescription {
+		name,
+		id,
+		chain_type,
+		authorities,
+		council,
+		endowments,
+		vesting,
+		boot_nodes,
+		telemetry_endpoints,
+		protocol_id,
+		properties,
+	} = description;
+	// Further properties may be added, but the runtime decides those of its token.
+	let mut all_properties = self::properties();
+	all_properties.extend(properties.unwrap_or_default());
+	check_properties(&all_properties)?;
+	let telemetry = if telemetry_endpoints.is_empty() {
+		None
+	} else {
+		Some(
+			TelemetryEndpoints::new(telemetry_endpoints)
+				.map_err(|e| format!("Invalid telemetry endpoint: {}", e))?,
+		)
+	};
+
+	Ok(ChainSpec::from_genesis(
+		&name,
+		&id,
+		chain_type,
+		move || {
+			testnet_genesis(
+				wasm_binary,
+				authorities
+					.iter()
+					.map(|a| {
+						(a.account.clone(), a.aura.clone(), a.grandpa.clone(), a.im_online.clone())
+					})
+					.collect(),
+				council.clone(),
+				endowments.iter().map(|e| (e.account.clone(), e.amount)).collect(),
+				None,
+				vec![],
+				vesting.clone(),
+				false,
+			)
+		},
+		boot_nodes,
+		telemetry,
+		protocol_id.as_deref(),
+		Some(all_properties),
+		None,
+	))
+}
+
+/// The staging network, as described by `res/staging.toml`.
+pub fn staging_config() -> Result<ChainSpec, String> {
+	let description: ChainDescription = toml::from_str(include_str!("../res/staging.toml"))
+		.map_err(|e| format!("Error parsing the staging description: {}", e))?;
+	custom_config(description.check()?)
+}
+
+/// Endow each of `accounts` with 1 << 60.
+fn endow(accounts: Vec<AccountId>) -> Vec<(AccountId, Balance)> {
+	accounts.into_iter().map(|k| (k, 1 << 60)).collect()
 }
 
-pub fn development_config() -> Result<ChainSpec, String> {
+pub fn development_config(vesting: Vec<VestingEntry>) -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
 
 	Ok(ChainSpec::from_genesis(
@@ -50,15 +293,20 @@ pub fn development_config() -> Result<ChainSpec, String> {
 				wasm_binary,
 				// Initial PoA authorities
 				vec![authority_keys_from_seed("Alice")],
-				// Sudo account
-				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				// Council members
+				vec![get_account_id_from_seed::<sr25519::Public>("Alice")],
 				// Pre-funded accounts
-				vec![
+				endow(vec![
 					get_account_id_from_seed::<sr25519::Public>("Alice"),
 					get_account_id_from_seed::<sr25519::Public>("Bob"),
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-				],
+				]),
+				// Initial `Something` value
+				Some(42),
+				// Initial template registry entries
+				vec![(get_account_id_from_seed::<sr25519::Public>("Alice"), 0, 42)],
+				vesting.clone(),
 				true,
 			)
 		},
@@ -69,32 +317,38 @@ pub fn development_config() -> Result<ChainSpec, String> {
 		// Protocol ID
 		None,
 		// Properties
-		None,
+		Some(properties()),
 		// Extensions
 		None,
 	))
 }
 
-pub fn local_testnet_config() -> Result<ChainSpec, String> {
+/// The id of the chain built by [`local_testnet_config`].
+const LOCAL_TESTNET_ID: &str = "local_testnet";
+
+pub fn local_testnet_config(vesting: Vec<VestingEntry>) -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
 
 	Ok(ChainSpec::from_genesis(
 		// Name
 		"Local Testnet",
 		// ID
-		"local_testnet",
+		LOCAL_TESTNET_ID,
 		ChainType::Local,
 		move || {
 			testnet_genesis(
 				wasm_binary,
 				// Initial PoA authorities
 				vec![authority_keys_from_seed("Alice"), authority_keys_from_seed("Bob")],
-				// Sudo account
-				get_account_id_from_seed::<sr25519::Public>("Alice"),
-				// Pre-funded accounts
+				// Council members
 				vec![
 					get_account_id_from_seed::<sr25519::Public>("Alice"),
 					get_account_id_from_seed::<sr25519::Public>("Bob"),
+				],
+				// Pre-funded accounts
+				endow(vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
 					get_account_id_from_seed::<sr25519::Public>("Charlie"),
 					get_account_id_from_seed::<sr25519::Public>("Dave"),
 					get_account_id_from_seed::<sr25519::Public>("Eve"),
@@ -105,7 +359,15 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
+				]),
+				// Initial `Something` value
+				Some(42),
+				// Initial template registry entries
+				vec![
+					(get_account_id_from_seed::<sr25519::Public>("Alice"), 0, 42),
+					(get_account_id_from_seed::<sr25519::Public>("Bob"), 0, 43),
 				],
+				vesting.clone(),
 				true,
 			)
 		},
@@ -116,39 +378,486 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 		// Protocol ID
 		None,
 		// Properties
-		None,
+		Some(properties()),
 		// Extensions
 		None,
 	))
 }
 
+/// Networks whose raw chain spec is built into the node, so that nodes built from different
+/// commits agree on their genesis: `(id, raw chain spec, pinned genesis hash)`.
+///
+/// To add a network, write its specs with `generate-spec`, save the raw one as `node/res/<id>.json`
+/// and add `("<id>", include_bytes!("../res/<id>.json"), hex_literal::hex!["<genesis hash>"])`.
+/// Pin the hash of block #0 reported by a node started on the raw spec, e.g. by the
+/// `chain_getBlockHash` RPC, rather than only the one printed by `generate-spec`.
+#[cfg(not(test))]
+const EMBEDDED_SPECS: &[(&str, &[u8], [u8; 32])] = &[];
+
+/// Tests embed a raw spec holding a single storage entry. Its genesis hash was computed apart from
+/// this crate, from the trie and header encodings of Substrate: the root of the trie holding the
+/// entry, and the header of block #0 with that state root and the empty extrinsics root.
+#[cfg(test)]
+const EMBEDDED_SPECS: &[(&str, &[u8], [u8; 32])] = &[(
+	"fixture",
+	include_bytes!("../res/fixture-raw.json"),
+	hex_literal::hex!["939effe27f1117e758ed48dd346b529029d66ecfe98f5f4ee7a36ea5249e2147"],
+)];
+
+/// The chain spec of the embedded network `id`, if there is one.
+pub fn embedded_config(id: &str) -> Option<Result<ChainSpec, String>> {
+	EMBEDDED_SPECS
+		.iter()
+		.find(|(embedded_id, _, _)| *embedded_id == id)
+		.map(|(_, json, genesis_hash)| load_pinned(*json, Hash::from(*genesis_hash)))
+}
+
+/// Load a chain spec and check that its genesis block has the hash `pinned`.
+fn load_pinned(json: impl Into<Cow<'static, [u8]>>, pinned: Hash) -> Result<ChainSpec, String> {
+	let spec = ChainSpec::from_json_bytes(json)?;
+	let genesis_hash = genesis_hash(&spec)?;
+	if genesis_hash != pinned {
+		return Err(format!(
+			"Chain spec {} has genesis hash {:?} instead of the pinned {:?}",
+			spec.id(),
+			genesis_hash,
+			pinned
+		))
+	}
+	Ok(spec)
+}
+
+/// The hash of the genesis block of the chain described by `spec`.
+pub fn genesis_hash(spec: &dyn sc_service::ChainSpec) -> Result<Hash, String> {
+	let storage = spec.as_storage_builder().build_storage()?;
+	let child_roots = storage.children_default.values().map(|child| {
+		let root = BlakeTwo256::trie_root(child.data.clone().into_iter().collect());
+		(child.child_info.prefixed_storage_key().into_inner(), root.as_bytes().to_vec())
+	});
+	let state_root = BlakeTwo256::trie_root(storage.top.into_iter().chain(child_roots).collect());
+	let extrinsics_root = BlakeTwo256::trie_root(Vec::new());
+
+	Ok(Header::new(0, extrinsics_root, state_root, Default::default(), Default::default()).hash())
+}
+
+/// The pallets whose state `fork_config` takes from the snapshot of another chain.
+const FORKED_PALLETS: &[&str] = &["Balances", "TemplateModule"];
+
+type AccountInfo = frame_system::AccountInfo<Index, pallet_balances::AccountData<Balance>>;
+
+/// The prefix under which the storage item `item` of the pallet `pallet` is stored.
+fn storage_prefix(pallet: &str, item: &str) -> Vec<u8> {
+	[twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat()
+}
+
+/// Build the chain spec of a chain described by `description`, with the state of the
+/// `FORKED_PALLETS` taken from `snapshot`, a raw chain spec written by `export-state`.
+///
+/// The balances are stored in the accounts of `System`, which are taken from the snapshot as well,
+/// with the `endowments` of the description credited on top. Their locks and the references other
+/// pallets hold to them are dropped with the pallets that are not forked, and the total issuance is
+/// recomputed. Funds reserved by those pallets stay reserved. The description cannot have `vesting`
+/// schedules, since they would be left without the locks that enforce them.
+///
+/// `NextUnsignedAt` of `TemplateModule` is a block number of the snapshot chain, so it is reset for
+/// the new chain, which starts again from block #0.
+pub fn fork_config(description: ChainDescription, snapshot: &Path) -> Result<ChainSpec, String> {
+	if !description.vesting.is_empty() {
+		return Err("A forked chain cannot have vesting schedules, its locks are dropped".into())
+	}
+	let mut endowments = BTreeMap::<AccountId, Balance>::new();
+	for endowment in &description.endowments {
+		*endowments.entry(endowment.account.clone()).or_default() += endowment.amount;
+	}
+	let mut spec = custom_config(description)?;
+	let mut storage = spec.build_storage()?;
+	let snapshot = ChainSpec::from_json_file(snapshot.to_path_buf())?.build_storage()?;
+
+	let forked = |key: &[u8]| {
+		FORKED_PALLETS
+			.iter()
+			.any(|pallet| key.starts_with(&twox_128(pallet.as_bytes())))
+	};
+	let accounts = storage_prefix("System", "Account");
+	let locks = storage_prefix("Balances", "Locks");
+	let next_unsigned_at = storage_prefix("TemplateModule", "NextUnsignedAt");
+
+	storage.top.retain(|key, _| !forked(key));
+	for (key, value) in snapshot.top {
+		if key.starts_with(&accounts) {
+			let mut account = AccountInfo::decode(&mut &value[..])
+				.map_err(|e| format!("Invalid account in snapshot: {}", e))?;
+			// The key ends with the account id, after its `Blake2_128Concat` hash.
+			let who = AccountId::decode(&mut &key[accounts.len() + 16..])
+				.map_err(|e| format!("Invalid account key in snapshot: {}", e))?;
+			// Endowments of accounts that are not in the snapshot are in the new genesis already.
+			let endowment = endowments.get(&who).copied().unwrap_or_default();
+			account.data.free = account.data.free.saturating_add(endowment);
+			// Keep the references the new genesis holds, such as those of the session keys.
+			let genesis = storage
+				.top
+				.get(&key)
+				.and_then(|value| AccountInfo::decode(&mut &value[..]).ok())
+				.unwrap_or_default();
+			account.consumers = genesis.consumers;
+			account.providers = account.providers.max(genesis.providers);
+			account.data.misc_frozen = 0;
+			account.data.fee_frozen = 0;
+			storage.top.insert(key, account.encode());
+		} else if forked(&key) && !key.starts_with(&locks) && key != next_unsigned_at {
+			storage.top.insert(key, value);
+		}
+	}
+
+	let total_issuance = storage
+		.top
+		.iter()
+		.filter(|(key, _)| key.starts_with(&accounts))
+		.filter_map(|(_, value)| AccountInfo::decode(&mut &value[..]).ok())
+		.fold(0 as Balance, |total, account| {
+			total.saturating_add(account.data.free).saturating_add(account.data.reserved)
+		});
+	let key = storage_prefix("Balances", "TotalIssuance");
+	storage.top.insert(key, total_issuance.encode());
+
+	sc_service::ChainSpec::set_storage(&mut spec, storage);
+	Ok(spec)
+}
+
+/// The seeds from which the `dev` and `local` chains derive their keys. Their secret keys are known
+/// to everyone.
+const DEV_SEEDS: &[&str] = &["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"];
+
+/// Refuse a chain whose genesis validators have keys derived from one of the `DEV_SEEDS`.
+///
+/// Development chains and the built-in `local` chain are run with such keys by design, so they are
+/// not checked.
+pub fn check_authorities(spec: &dyn sc_service::ChainSpec) -> Result<(), String> {
+	if spec.chain_type() == ChainType::Development || spec.id() == LOCAL_TESTNET_ID {
+		return Ok(())
+	}
+
+	let storage = spec.as_storage_builder().build_storage()?;
+	let key = storage_prefix("Session", "QueuedKeys");
+	let value = storage
+		.top
+		.get(&key)
+		.ok_or_else(|| format!("Chain {} has no session keys in genesis", spec.id()))?;
+	let queued_keys = Vec::<(AccountId, SessionKeys)>::decode(&mut &value[..])
+		.map_err(|e| format!("Invalid session keys in genesis: {}", e))?;
+
+	for seed in DEV_SEEDS.iter().flat_map(|s| [s.to_string(), format!("{}//stash", s)]) {
+		let (account, aura, grandpa, im_online) = authority_keys_from_seed(&seed);
+		for (validator, keys) in &queued_keys {
+			if *validator == account ||
+				keys.aura == aura ||
+				keys.grandpa == grandpa ||
+				keys.im_online == im_online
+			{
+				return Err(format!(
+					"Validator {} of the {:?} chain {} has keys of the well-known seed `//{}`",
+					validator,
+					spec.chain_type(),
+					spec.id(),
+					seed
+				))
+			}
+		}
+	}
+	Ok(())
+}
+
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
-	initial_authorities: Vec<(AuraId, GrandpaId)>,
-	root_key: AccountId,
-	endowed_accounts: Vec<AccountId>,
+	initial_authorities: Vec<(AccountId, AuraId, GrandpaId, ImOnlineId)>,
+	council_members: Vec<AccountId>,
+	endowments: Vec<(AccountId, Balance)>,
+	initial_something: Option<u32>,
+	initial_registry: Vec<(AccountId, u32, u32)>,
+	vesting: Vec<VestingEntry>,
 	_enable_println: bool,
 ) -> GenesisConfig {
+	// Credit the endowments, and what vests on top of them.
+	let mut balances = BTreeMap::<AccountId, Balance>::new();
+	let vested = vesting.iter().map(|e| (e.account.clone(), e.amount));
+	for (account, amount) in endowments.into_iter().chain(vested) {
+		*balances.entry(account).or_default() += amount;
+	}
+	// Whatever does not vest is liquid from the start.
+	let vesting = vesting
+		.into_iter()
+		.map(|e| (e.account.clone(), e.start, e.period, balances[&e.account] - e.amount))
+		.collect();
+
 	GenesisConfig {
 		system: SystemConfig {
 			// Add Wasm runtime to storage.
 			code: wasm_binary.to_vec(),
 		},
-		balances: BalancesConfig {
-			// Configure endowed accounts with initial balance of 1 << 60.
-			balances: endowed_accounts.iter().cloned().map(|k| (k, 1 << 60)).collect(),
+		balances: BalancesConfig { balances: balances.into_iter().collect() },
+		validator_set: ValidatorSetConfig {
+			validators: initial_authorities.iter().map(|x| x.0.clone()).collect(),
 		},
-		aura: AuraConfig {
-			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
-		},
-		grandpa: GrandpaConfig {
-			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
-		},
-		sudo: SudoConfig {
-			// Assign network admin rights.
-			key: root_key,
+		session: SessionConfig {
+			keys: initial_authorities
+				.iter()
+				.map(|x| {
+					(x.0.clone(), x.0.clone(), session_keys(x.1.clone(), x.2.clone(), x.3.clone()))
+				})
+				.collect(),
 		},
+		// Authorities are set from the session keys above.
+		aura: AuraConfig { authorities: vec![] },
+		grandpa: GrandpaConfig { authorities: vec![] },
+		im_online: ImOnlineConfig { keys: vec![] },
+		// Privileged calls need a referendum, which the council can put up and fast-track.
+		council: CouncilConfig { members: council_members, phantom: Default::default() },
+		democracy: Default::default(),
+		treasury: Default::default(),
 		transaction_payment: Default::default(),
+		vesting: VestingConfig { vesting },
+		template_module: TemplateModuleConfig {
+			something: initial_something,
+			registry: initial_registry,
+		},
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::{blake2_128, storage::Storage};
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+	const ALICE_ED25519: &str = "5FA9nQDVg267DEd8m1ZypXLBnvN7SFxYwV7ndqSYGiN9TTpu";
+
+	#[test]
+	fn chain_description_reads_toml() {
+		let description: ChainDescription = toml::from_str(&format!(
+			r#"
+			name = "Staging"
+			id = "staging"
+			chainType = "Live"
+			council = ["{alice}"]
+			telemetryEndpoints = [["wss://telemetry.polkadot.io/submit/", 0]]
+			properties = {{ tokenSymbol = "UNIT" }}
+
+			[[authorities]]
+			account = "{alice}"
+			aura = "{alice}"
+			grandpa = "{alice_ed25519}"
+			imOnline = "{alice}"
+
+			[[endowments]]
+			account = "{alice}"
+			amount = 1000
+			"#,
+			alice = ALICE,
+			alice_ed25519 = ALICE_ED25519,
+		))
+		.unwrap();
+
+		let alice = authority_keys_from_seed("Alice");
+		assert_eq!(description.chain_type, ChainType::Live);
+		assert_eq!(description.authorities[0].account, alice.0);
+		assert_eq!(description.authorities[0].grandpa, alice.2);
+		assert_eq!(description.council, vec![alice.0.clone()]);
+		assert_eq!(description.endowments[0].amount, 1000);
+		assert!(description.boot_nodes.is_empty());
+		assert_eq!(description.telemetry_endpoints[0].1, 0);
+		assert_eq!(description.properties.unwrap()["tokenSymbol"], "UNIT");
+	}
+
+	#[test]
+	fn chain_description_rejects_unknown_fields() {
+		let description = format!(
+			r#"{{"name": "Staging", "id": "staging", "chainType": "Live", "authorities": [],
+			"council": [], "sudo": "{}"}}"#,
+			ALICE
+		);
+		assert!(serde_json::from_str::<ChainDescription>(&description).is_err());
+	}
+
+	#[test]
+	fn properties_must_match_the_runtime() {
+		assert_eq!(check_properties(&properties()), Ok(()));
+
+		let mut other_format = properties();
+		other_format.insert("ss58Format".into(), 0.into());
+		assert!(check_properties(&other_format).is_err());
+
+		let mut no_symbol = properties();
+		no_symbol.remove("tokenSymbol");
+		assert!(check_properties(&no_symbol).is_err());
+	}
+
+	#[test]
+	fn vesting_below_existential_deposit_is_refused() {
+		let vesting = |amount| VestingEntry {
+			account: get_account_id_from_seed::<sr25519::Public>("Charlie"),
+			amount,
+			start: 0,
+			period: 1,
+		};
+		assert_eq!(check_vesting(&[vesting(ExistentialDeposit::get())]), Ok(()));
+		assert!(check_vesting(&[vesting(ExistentialDeposit::get() - 1)]).is_err());
+	}
+
+	/// Description of a chain of type `chain_type` validated by the keys derived from `seed`.
+	fn description(chain_type: ChainType, seed: &str) -> ChainDescription {
+		let (account, aura, grandpa, im_online) = authority_keys_from_seed(seed);
+		ChainDescription {
+			name: "Staging".into(),
+			id: "staging".into(),
+			chain_type,
+			authorities: vec![AuthorityKeys { account: account.clone(), aura, grandpa, im_online }],
+			council: vec![account],
+			endowments: vec![],
+			vesting: vec![],
+			boot_nodes: vec![],
+			telemetry_endpoints: vec![],
+			protocol_id: None,
+			properties: None,
+		}
+	}
+
+	#[test]
+	fn staging_has_operator_keys_bootnodes_and_telemetry() {
+		let staging = staging_config().unwrap();
+		assert_eq!(check_authorities(&staging), Ok(()));
+		assert_eq!(staging.id(), "staging");
+		assert_eq!(staging.boot_nodes().len(), 3);
+		assert!(staging.telemetry_endpoints().is_some());
+	}
+
+	#[test]
+	fn chain_with_dev_keys_is_refused() {
+		let live = custom_config(description(ChainType::Live, "Bob")).unwrap();
+		assert!(check_authorities(&live).is_err());
+		let local = custom_config(description(ChainType::Local, "Bob//stash")).unwrap();
+		assert!(check_authorities(&local).is_err());
+
+		let dev = custom_config(description(ChainType::Development, "Bob")).unwrap();
+		assert_eq!(check_authorities(&dev), Ok(()));
+		assert_eq!(check_authorities(&local_testnet_config(vec![]).unwrap()), Ok(()));
+		let staging = custom_config(description(ChainType::Live, "Staging")).unwrap();
+		assert_eq!(check_authorities(&staging), Ok(()));
+	}
+
+	#[test]
+	fn chain_without_session_keys_is_refused() {
+		let mut spec = custom_config(description(ChainType::Live, "Staging")).unwrap();
+		let mut storage = spec.build_storage().unwrap();
+		storage.top.remove(&storage_prefix("Session", "QueuedKeys"));
+		sc_service::ChainSpec::set_storage(&mut spec, storage);
+
+		assert!(check_authorities(&spec).is_err());
+	}
+
+	#[test]
+	fn embedded_spec_must_have_pinned_genesis_hash() {
+		let loaded = embedded_config("fixture").unwrap().unwrap();
+		assert_eq!(loaded.id(), "fixture");
+		assert!(embedded_config("unknown").is_none());
+
+		let (_, json, _) = EMBEDDED_SPECS[0];
+		assert!(load_pinned(json, Hash::zero()).is_err());
+	}
+
+	/// Fork `snapshot` into the chain of `description`, through a snapshot file named `name`.
+	fn fork(snapshot: &ChainSpec, description: ChainDescription, name: &str) -> ChainSpec {
+		let path = std::env::temp_dir().join(format!("{}-{}.json", name, std::process::id()));
+		fs::write(&path, sc_service::ChainSpec::as_json(snapshot, true).unwrap()).unwrap();
+		let forked = fork_config(description, &path);
+		fs::remove_file(&path).unwrap();
+		forked.unwrap()
+	}
+
+	fn account(storage: &Storage, who: &AccountId) -> AccountInfo {
+		let key = [
+			storage_prefix("System", "Account"),
+			blake2_128(who.as_ref()).to_vec(),
+			who.as_ref().to_vec(),
+		]
+		.concat();
+		AccountInfo::decode(&mut &storage.top[&key][..]).unwrap()
+	}
+
+	fn total_issuance(storage: &Storage) -> Balance {
+		let key = storage_prefix("Balances", "TotalIssuance");
+		Balance::decode(&mut &storage.top[&key][..]).unwrap()
+	}
+
+	#[test]
+	fn fork_takes_balances_and_template_state_from_snapshot() {
+		let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+		let vesting =
+			vec![VestingEntry { account: charlie.clone(), amount: 1000, start: 0, period: 10 }];
+		let mut snapshot = local_testnet_config(vesting).unwrap();
+		// The snapshot chain is far ahead of the new one.
+		let next_unsigned_at = storage_prefix("TemplateModule", "NextUnsignedAt");
+		let mut storage = snapshot.build_storage().unwrap();
+		storage.top.insert(next_unsigned_at.clone(), 100_000u32.encode());
+		sc_service::ChainSpec::set_storage(&mut snapshot, storage);
+
+		let forked = fork(&snapshot, description(ChainType::Live, "Rehearsal"), "fork-state");
+		assert_eq!(check_authorities(&forked), Ok(()));
+
+		let snapshot = snapshot.build_storage().unwrap();
+		let storage = forked.build_storage().unwrap();
+		let template = twox_128(b"TemplateModule");
+		let template_state = |storage: &Storage| {
+			storage
+				.top
+				.iter()
+				.filter(|(key, _)| key.starts_with(&template) && **key != next_unsigned_at)
+				.collect::<Vec<_>>()
+		};
+		assert!(!template_state(&snapshot).is_empty());
+		assert_eq!(template_state(&storage), template_state(&snapshot));
+		assert!(!storage.top.contains_key(&next_unsigned_at));
+
+		assert_eq!(total_issuance(&storage), total_issuance(&snapshot));
+		let locks = storage_prefix("Balances", "Locks");
+		assert!(!storage.top.keys().any(|key| key.starts_with(&locks)));
+
+		// The vested funds are kept, but no longer locked without `Vesting`.
+		let charlie = account(&storage, &charlie);
+		assert_eq!(charlie.data.free, (1 << 60) + 1000);
+		assert_eq!(charlie.data.misc_frozen, 0);
+		assert_eq!(charlie.consumers, 0);
+	}
+
+	#[test]
+	fn fork_adds_endowments_to_snapshot_balances() {
+		let snapshot = local_testnet_config(vec![]).unwrap();
+		let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+		let validator = get_account_id_from_seed::<sr25519::Public>("Rehearsal");
+		let mut description = description(ChainType::Live, "Rehearsal");
+		description.endowments = vec![
+			Endowment { account: charlie.clone(), amount: 500 },
+			Endowment { account: validator.clone(), amount: 700 },
+		];
+
+		let storage = fork(&snapshot, description, "fork-endowments").build_storage().unwrap();
+		let snapshot = snapshot.build_storage().unwrap();
+
+		assert_eq!(account(&storage, &charlie).data.free, (1 << 60) + 500);
+		assert_eq!(account(&storage, &validator).data.free, 700);
+		assert_eq!(total_issuance(&storage), total_issuance(&snapshot) + 1200);
+	}
+
+	#[test]
+	fn fork_refuses_vesting() {
+		let mut description = description(ChainType::Live, "Rehearsal");
+		description.vesting = vec![VestingEntry {
+			account: get_account_id_from_seed::<sr25519::Public>("Rehearsal"),
+			amount: 1000,
+			start: 0,
+			period: 10,
+		}];
+
+		assert!(fork_config(description, Path::new("unused.json")).is_err());
 	}
 }
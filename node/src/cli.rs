@@ -1,4 +1,6 @@
+use crate::service::Sealing;
 use sc_cli::RunCmd;
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -8,6 +10,16 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	/// Seal blocks with manual seal instead of Aura and GRANDPA: `instant`, `manual` or
+	/// `interval=<ms>`. Meant for development and testing only.
+	#[structopt(long)]
+	pub sealing: Option<Sealing>,
+
+	/// JSON file of vesting schedules for the genesis of the `dev` and `local` chains: an array
+	/// of `{"account": <SS58 address>, "amount": <balance>, "start": <block>, "period": <blocks>}`.
+	#[structopt(long, parse(from_os_str))]
+	pub vesting: Option<PathBuf>,
 }
 
 #[derive(Debug, StructOpt)]
@@ -17,6 +29,9 @@ pub enum Subcommand {
 	/// Build a chain specification.
 	BuildSpec(sc_cli::BuildSpecCmd),
 
+	/// Generate the plain and raw chain specifications of a chain described in a file.
+	GenerateSpec(GenerateSpecCmd),
+
 	/// Validate blocks.
 	CheckBlock(sc_cli::CheckBlockCmd),
 
@@ -38,4 +53,37 @@ pub enum Subcommand {
 	/// The custom benchmark subcommand benchmarking runtime pallets.
 	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
 	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+
+	/// Try some command against runtime state, e.g. `on-runtime-upgrade` or `execute-block`
+	/// against a live node or a state snapshot file.
+	#[cfg(feature = "try-runtime")]
+	TryRuntime(try_runtime_cli::TryRuntimeCmd),
+
+	/// Try some command against runtime state. Note: `try-runtime` feature must be enabled.
+	#[cfg(not(feature = "try-runtime"))]
+	TryRuntime,
+}
+
+/// The `generate-spec` command.
+#[derive(Debug, StructOpt)]
+pub struct GenerateSpecCmd {
+	/// TOML or JSON file describing the chain: its `name`, `id` and `chainType`, the public keys of
+	/// its `authorities`, its `council`, its `endowments` and `vesting` schedules, and optionally
+	/// its `bootNodes`, `protocolId` and `properties`.
+	#[structopt(parse(from_os_str))]
+	pub description: PathBuf,
+
+	/// Where to write the plain chain specification. A forked chain has none.
+	#[structopt(long, parse(from_os_str))]
+	pub plain: Option<PathBuf>,
+
+	/// Where to write the raw chain specification.
+	#[structopt(long, parse(from_os_str))]
+	pub raw: PathBuf,
+
+	/// Fork the state of `Balances`, including the balances of all accounts, and of
+	/// `TemplateModule` from a chain spec written by `export-state`, to rehearse runtime upgrades
+	/// on it. Everything else, such as the validators and the council, comes from the description.
+	#[structopt(long, parse(from_os_str))]
+	pub fork: Option<PathBuf>,
 }
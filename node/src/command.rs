@@ -3,9 +3,10 @@ use crate::{
 	cli::{Cli, Subcommand},
 	service,
 };
-use node_template_runtime::Block;
+use node_template_runtime::{token, Block};
 use sc_cli::{ChainSpec, RuntimeVersion, SubstrateCli};
 use sc_service::PartialComponents;
+use sp_core::crypto::{set_default_ss58_version, Ss58AddressFormat};
 
 impl SubstrateCli for Cli {
 	fn impl_name() -> String {
@@ -33,11 +34,25 @@ impl SubstrateCli for Cli {
 	}
 
 	fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+		let vesting = || match &self.vesting {
+			Some(path) => chain_spec::vesting_from_json_file(path),
+			None => Ok(Vec::new()),
+		};
 		Ok(match id {
-			"dev" => Box::new(chain_spec::development_config()?),
-			"" | "local" => Box::new(chain_spec::local_testnet_config()?),
-			path =>
-				Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(path))?),
+			"dev" => Box::new(chain_spec::development_config(vesting()?)?),
+			"" | "local" => Box::new(chain_spec::local_testnet_config(vesting()?)?),
+			_ if self.vesting.is_some() =>
+				return Err("--vesting only applies to the `dev` and `local` chains".into()),
+			"staging" => Box::new(chain_spec::staging_config()?),
+			path if path.ends_with(".toml") => {
+				let path = std::path::Path::new(path);
+				Box::new(chain_spec::custom_config(chain_spec::ChainDescription::from_file(path)?)?)
+			},
+			path => match chain_spec::embedded_config(path) {
+				Some(spec) => Box::new(spec?),
+				None =>
+					Box::new(chain_spec::ChainSpec::from_json_file(std::path::PathBuf::from(path))?),
+			},
 		})
 	}
 
@@ -56,25 +71,43 @@ pub fn run() -> sc_cli::Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
 		},
+		Some(Subcommand::GenerateSpec(cmd)) => {
+			let description = chain_spec::ChainDescription::from_file(&cmd.description)?;
+			let spec = match &cmd.fork {
+				Some(_) if cmd.plain.is_some() =>
+					return Err("A forked chain has no plain chain spec".into()),
+				Some(snapshot) => chain_spec::fork_config(description, snapshot)?,
+				None => chain_spec::custom_config(description)?,
+			};
+			chain_spec::check_authorities(&spec)?;
+			if let Some(path) = &cmd.plain {
+				std::fs::write(path, ChainSpec::as_json(&spec, false)?)?;
+			}
+			std::fs::write(&cmd.raw, ChainSpec::as_json(&spec, true)?)?;
+			println!("Genesis hash: {:?}", chain_spec::genesis_hash(&spec)?);
+			Ok(())
+		},
 		Some(Subcommand::CheckBlock(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
 				let PartialComponents { client, task_manager, import_queue, .. } =
-					service::new_partial(&config)?;
+					service::new_partial(&config, cli.sealing)?;
 				Ok((cmd.run(client, import_queue), task_manager))
 			})
 		},
 		Some(Subcommand::ExportBlocks(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
-				let PartialComponents { client, task_manager, .. } = service::new_partial(&config)?;
+				let PartialComponents { client, task_manager, .. } =
+					service::new_partial(&config, cli.sealing)?;
 				Ok((cmd.run(client, config.database), task_manager))
 			})
 		},
 		Some(Subcommand::ExportState(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
-				let PartialComponents { client, task_manager, .. } = service::new_partial(&config)?;
+				let PartialComponents { client, task_manager, .. } =
+					service::new_partial(&config, cli.sealing)?;
 				Ok((cmd.run(client, config.chain_spec), task_manager))
 			})
 		},
@@ -82,7 +115,7 @@ pub fn run() -> sc_cli::Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
 				let PartialComponents { client, task_manager, import_queue, .. } =
-					service::new_partial(&config)?;
+					service::new_partial(&config, cli.sealing)?;
 				Ok((cmd.run(client, import_queue), task_manager))
 			})
 		},
@@ -94,7 +127,7 @@ pub fn run() -> sc_cli::Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
 				let PartialComponents { client, task_manager, backend, .. } =
-					service::new_partial(&config)?;
+					service::new_partial(&config, cli.sealing)?;
 				Ok((cmd.run(client, backend), task_manager))
 			})
 		},
@@ -108,11 +141,45 @@ pub fn run() -> sc_cli::Result<()> {
 				     `--features runtime-benchmarks`."
 					.into())
 			},
+		#[cfg(feature = "try-runtime")]
+		Some(Subcommand::TryRuntime(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|config| {
+				// we don't need any of the components of new_partial, just a runtime, or a task
+				// manager to do `async_run`.
+				let registry = config.prometheus_config.as_ref().map(|cfg| &cfg.registry);
+				let task_manager =
+					sc_service::TaskManager::new(config.tokio_handle.clone(), registry)
+						.map_err(|e| sc_cli::Error::Service(sc_service::Error::Prometheus(e)))?;
+				Ok((cmd.run::<Block, service::ExecutorDispatch>(config), task_manager))
+			})
+		},
+		#[cfg(not(feature = "try-runtime"))]
+		Some(Subcommand::TryRuntime) => Err("TryRuntime wasn't enabled when building the node. \
+				You can enable it with `--features try-runtime`."
+			.into()),
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
+			let spec = &runner.config().chain_spec;
+			chain_spec::check_properties(&spec.properties())?;
+			chain_spec::check_authorities(spec.as_ref())?;
+			set_default_ss58_version(Ss58AddressFormat::custom(token::SS58_FORMAT.into()));
+			let sealing = cli.sealing;
 			runner.run_node_until_exit(|config| async move {
-				service::new_full(config).map_err(sc_cli::Error::Service)
+				service::new_full(config, sealing).map_err(sc_cli::Error::Service)
 			})
 		},
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn embedded_chains_are_loaded_by_id() {
+		let cli = Cli::from_iter(&["node-template"]);
+		assert_eq!(cli.load_spec("fixture").unwrap().id(), "fixture");
+		assert_eq!(cli.load_spec("dev").unwrap().id(), "dev");
+	}
+}
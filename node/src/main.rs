@@ -8,6 +8,8 @@ mod cli;
 mod command;
 mod rpc;
 
+use node_template::remote_keystore;
+
 fn main() -> sc_cli::Result<()> {
 	command::run()
 }
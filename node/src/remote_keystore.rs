@@ -0,0 +1,648 @@
+//! A keystore that keeps its keys in a separate signer process.
+//!
+//! The node talks to the signer over a Unix socket, passed as `--keystore-uri unix://<path>`.
+//! Every call opens a connection, writes one JSON encoded [`Request`] followed by a newline and
+//! reads back one line holding the JSON encoded `Result` of the call. A signer that does not accept
+//! the connection or answer within [`TIMEOUT`] is treated as unavailable, so that it cannot stall
+//! block production. The blocking socket I/O of the asynchronous [`CryptoStore`] methods runs on a
+//! thread of its own, so it does not hold up the executor of the caller. Only sr25519 and ed25519
+//! keys are supported.
+//!
+//! [`LocalSigner`] implements the signer side on top of an in-memory keystore, as a stand-in
+//! for a real signing service in tests and local setups.
+
+use async_trait::async_trait;
+use futures::channel::oneshot;
+use sc_keystore::LocalKeystore;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sp_core::{
+	crypto::{CryptoTypeId, CryptoTypePublicPair, KeyTypeId},
+	ecdsa, ed25519, sr25519, Bytes,
+};
+use sp_keystore::{
+	vrf::{VRFSignature, VRFTranscriptData},
+	CryptoStore, Error, SyncCryptoStore,
+};
+use std::{
+	convert::TryFrom,
+	io::{self, BufRead, BufReader, Write},
+	os::unix::net::{UnixListener, UnixStream},
+	path::{Path, PathBuf},
+	sync::mpsc,
+	time::Duration,
+};
+
+/// How long to wait for the signer to accept a connection, accept a request or answer it.
+pub const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Connect to the Unix socket at `path`, giving up after [`TIMEOUT`].
+///
+/// `UnixStream` has no connect timeout, and connecting blocks while the backlog of the signer is
+/// full, so the connection is made on a thread of its own.
+fn connect(path: &Path) -> io::Result<UnixStream> {
+	let (sender, receiver) = mpsc::channel();
+	let path = path.to_path_buf();
+	std::thread::spawn(move || {
+		let _ = sender.send(UnixStream::connect(path));
+	});
+	receiver.recv_timeout(TIMEOUT).unwrap_or_else(|_| {
+		Err(io::Error::new(io::ErrorKind::TimedOut, "Connecting to the signer timed out"))
+	})
+}
+
+/// The key schemes the remote keystore supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scheme {
+	Sr25519,
+	Ed25519,
+}
+
+impl Scheme {
+	fn crypto_id(self) -> CryptoTypeId {
+		match self {
+			Self::Sr25519 => sr25519::CRYPTO_ID,
+			Self::Ed25519 => ed25519::CRYPTO_ID,
+		}
+	}
+
+	fn from_crypto_id(id: CryptoTypeId) -> Option<Self> {
+		match id {
+			sr25519::CRYPTO_ID => Some(Self::Sr25519),
+			ed25519::CRYPTO_ID => Some(Self::Ed25519),
+			_ => None,
+		}
+	}
+}
+
+/// A call to the signer. Key types and binary data are hex encoded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "camelCase")]
+pub enum Request {
+	/// Public keys of the given scheme and key type. Returns a list of public keys.
+	PublicKeys { key_type: Bytes, scheme: Scheme },
+	/// Generate a new key, from `seed` if given. Returns the new public key.
+	GenerateNew { key_type: Bytes, scheme: Scheme, seed: Option<String> },
+	/// Store the key given by `suri`. Returns nothing.
+	InsertUnknown { key_type: Bytes, suri: String, public: Bytes },
+	/// Whether the signer holds all given `(public key, key type)` pairs. Returns a boolean.
+	HasKeys { keys: Vec<(Bytes, Bytes)> },
+	/// Sign `message` with the given key. Returns the signature, or nothing if the key is
+	/// unknown.
+	Sign { key_type: Bytes, scheme: Scheme, public: Bytes, message: Bytes },
+}
+
+/// The reply to a [`Request`], either the JSON encoded return value or an error message.
+pub type Response = Result<serde_json::Value, String>;
+
+fn key_type_id(key_type: &Bytes) -> Result<KeyTypeId, String> {
+	<[u8; 4]>::try_from(&key_type[..])
+		.map(KeyTypeId)
+		.map_err(|_| format!("Invalid key type {:?}", key_type))
+}
+
+/// A keystore that forwards every operation to a signer behind a Unix socket.
+#[derive(Clone)]
+pub struct RemoteKeystore {
+	path: PathBuf,
+}
+
+impl RemoteKeystore {
+	/// Connect to the signer at `uri`, which must have the form `unix://<path>`.
+	pub fn open(uri: &str) -> Result<Self, String> {
+		let path = uri.strip_prefix("unix://").ok_or_else(|| {
+			format!("Unsupported remote keystore URI `{}`, expected `unix://<path>`", uri)
+		})?;
+		// Fail at startup rather than on the first signing request.
+		connect(Path::new(path)).map_err(|e| format!("Cannot reach signer: {}", e))?;
+
+		Ok(Self { path: path.into() })
+	}
+
+	fn call<R: DeserializeOwned>(&self, request: &Request) -> Result<R, Error> {
+		let mut line = serde_json::to_vec(request).map_err(|e| Error::Other(e.to_string()))?;
+		line.push(b'\n');
+		let line = self.exchange(&line).map_err(|e| {
+			Error::Other(format!("Signer at {} is unavailable: {}", self.path.display(), e))
+		})?;
+
+		let response: Response =
+			serde_json::from_str(&line).map_err(|e| Error::Other(e.to_string()))?;
+		let value = response.map_err(Error::Other)?;
+
+		serde_json::from_value(value).map_err(|e| Error::Other(e.to_string()))
+	}
+
+	/// Send one request line to the signer and read back its answer, within [`TIMEOUT`].
+	fn exchange(&self, request: &[u8]) -> io::Result<String> {
+		let stream = connect(&self.path)?;
+		stream.set_read_timeout(Some(TIMEOUT))?;
+		stream.set_write_timeout(Some(TIMEOUT))?;
+
+		(&stream).write_all(request)?;
+		let mut line = String::new();
+		BufReader::new(&stream).read_line(&mut line)?;
+		Ok(line)
+	}
+
+	/// Public keys of the given scheme and key type.
+	///
+	/// The keystore traits have no room for errors here, so a failing signer is logged and
+	/// reported as holding no keys.
+	fn public_keys(&self, id: KeyTypeId, scheme: Scheme) -> Vec<Bytes> {
+		self.call(&Request::PublicKeys { key_type: id.0.to_vec().into(), scheme })
+			.unwrap_or_else(|e| {
+				log::error!("Cannot list {:?} keys of type {:?}: {}", scheme, id, e);
+				Vec::new()
+			})
+	}
+
+	fn generate_new(
+		&self,
+		id: KeyTypeId,
+		scheme: Scheme,
+		seed: Option<&str>,
+	) -> Result<Bytes, Error> {
+		self.call(&Request::GenerateNew {
+			key_type: id.0.to_vec().into(),
+			scheme,
+			seed: seed.map(Into::into),
+		})
+	}
+
+	/// Run `f` on this keystore from a thread of its own and wait for its result without blocking.
+	async fn unblock<R, F>(&self, f: F) -> R
+	where
+		R: Send + 'static,
+		F: FnOnce(&Self) -> R + Send + 'static,
+	{
+		let (sender, receiver) = oneshot::channel();
+		let keystore = self.clone();
+		std::thread::spawn(move || {
+			let _ = sender.send(f(&keystore));
+		});
+		receiver.await.expect("the thread sends a result unless `f` panics; qed")
+	}
+}
+
+fn ecdsa_unsupported() -> Error {
+	Error::Other("ECDSA keys are not supported by the remote keystore".into())
+}
+
+impl SyncCryptoStore for RemoteKeystore {
+	fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+		self.public_keys(id, Scheme::Sr25519)
+			.iter()
+			.filter_map(|public| sr25519::Public::try_from(&public[..]).ok())
+			.collect()
+	}
+
+	fn sr25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<sr25519::Public, Error> {
+		let public = self.generate_new(id, Scheme::Sr25519, seed)?;
+		sr25519::Public::try_from(&public[..])
+			.map_err(|_| Error::ValidationError("Invalid sr25519 public key".into()))
+	}
+
+	fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+		self.public_keys(id, Scheme::Ed25519)
+			.iter()
+			.filter_map(|public| ed25519::Public::try_from(&public[..]).ok())
+			.collect()
+	}
+
+	fn ed25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ed25519::Public, Error> {
+		let public = self.generate_new(id, Scheme::Ed25519, seed)?;
+		ed25519::Public::try_from(&public[..])
+			.map_err(|_| Error::ValidationError("Invalid ed25519 public key".into()))
+	}
+
+	fn ecdsa_public_keys(&self, _id: KeyTypeId) -> Vec<ecdsa::Public> {
+		Vec::new()
+	}
+
+	fn ecdsa_generate_new(
+		&self,
+		_id: KeyTypeId,
+		_seed: Option<&str>,
+	) -> Result<ecdsa::Public, Error> {
+		Err(ecdsa_unsupported())
+	}
+
+	fn insert_unknown(&self, id: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
+		self.call(&Request::InsertUnknown {
+			key_type: id.0.to_vec().into(),
+			suri: suri.into(),
+			public: public.to_vec().into(),
+		})
+		.map_err(|_| ())
+	}
+
+	fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>,
+	) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		let available = SyncCryptoStore::keys(self, id)?;
+		Ok(keys.into_iter().filter(|key| available.contains(key)).collect())
+	}
+
+	fn keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		let sr25519 = SyncCryptoStore::sr25519_public_keys(self, id).into_iter().map(Into::into);
+		let ed25519 = SyncCryptoStore::ed25519_public_keys(self, id).into_iter().map(Into::into);
+		Ok(sr25519.chain(ed25519).collect())
+	}
+
+	fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		let keys = public_keys
+			.iter()
+			.map(|(public, id)| (public.clone().into(), id.0.to_vec().into()))
+			.collect();
+		self.call(&Request::HasKeys { keys }).unwrap_or_else(|e| {
+			log::error!("Cannot look up keys: {}", e);
+			false
+		})
+	}
+
+	fn sign_with(
+		&self,
+		id: KeyTypeId,
+		key: &CryptoTypePublicPair,
+		msg: &[u8],
+	) -> Result<Option<Vec<u8>>, Error> {
+		let scheme = Scheme::from_crypto_id(key.0).ok_or(Error::KeyNotSupported(id))?;
+		let signature: Option<Bytes> = self.call(&Request::Sign {
+			key_type: id.0.to_vec().into(),
+			scheme,
+			public: key.1.clone().into(),
+			message: msg.to_vec().into(),
+		})?;
+		Ok(signature.map(|signature| signature.0))
+	}
+
+	fn sr25519_vrf_sign(
+		&self,
+		_key_type: KeyTypeId,
+		_public: &sr25519::Public,
+		_transcript_data: VRFTranscriptData,
+	) -> Result<Option<VRFSignature>, Error> {
+		Err(Error::Other("VRF signing is not supported by the remote keystore".into()))
+	}
+
+	fn ecdsa_sign_prehashed(
+		&self,
+		_id: KeyTypeId,
+		_public: &ecdsa::Public,
+		_msg: &[u8; 32],
+	) -> Result<Option<ecdsa::Signature>, Error> {
+		Err(ecdsa_unsupported())
+	}
+}
+
+#[async_trait]
+impl CryptoStore for RemoteKeystore {
+	async fn sr25519_public_keys(&self, id: KeyTypeId) -> Vec<sr25519::Public> {
+		self.unblock(move |keystore| SyncCryptoStore::sr25519_public_keys(keystore, id))
+			.await
+	}
+
+	async fn sr25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<sr25519::Public, Error> {
+		let seed = seed.map(str::to_owned);
+		self.unblock(move |keystore| {
+			SyncCryptoStore::sr25519_generate_new(keystore, id, seed.as_deref())
+		})
+		.await
+	}
+
+	async fn ed25519_public_keys(&self, id: KeyTypeId) -> Vec<ed25519::Public> {
+		self.unblock(move |keystore| SyncCryptoStore::ed25519_public_keys(keystore, id))
+			.await
+	}
+
+	async fn ed25519_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ed25519::Public, Error> {
+		let seed = seed.map(str::to_owned);
+		self.unblock(move |keystore| {
+			SyncCryptoStore::ed25519_generate_new(keystore, id, seed.as_deref())
+		})
+		.await
+	}
+
+	async fn ecdsa_public_keys(&self, id: KeyTypeId) -> Vec<ecdsa::Public> {
+		SyncCryptoStore::ecdsa_public_keys(self, id)
+	}
+
+	async fn ecdsa_generate_new(
+		&self,
+		id: KeyTypeId,
+		seed: Option<&str>,
+	) -> Result<ecdsa::Public, Error> {
+		SyncCryptoStore::ecdsa_generate_new(self, id, seed)
+	}
+
+	async fn insert_unknown(&self, id: KeyTypeId, suri: &str, public: &[u8]) -> Result<(), ()> {
+		let (suri, public) = (suri.to_owned(), public.to_vec());
+		self.unblock(move |keystore| SyncCryptoStore::insert_unknown(keystore, id, &suri, &public))
+			.await
+	}
+
+	async fn supported_keys(
+		&self,
+		id: KeyTypeId,
+		keys: Vec<CryptoTypePublicPair>,
+	) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		self.unblock(move |keystore| SyncCryptoStore::supported_keys(keystore, id, keys))
+			.await
+	}
+
+	async fn keys(&self, id: KeyTypeId) -> Result<Vec<CryptoTypePublicPair>, Error> {
+		self.unblock(move |keystore| SyncCryptoStore::keys(keystore, id)).await
+	}
+
+	async fn has_keys(&self, public_keys: &[(Vec<u8>, KeyTypeId)]) -> bool {
+		let public_keys = public_keys.to_vec();
+		self.unblock(move |keystore| SyncCryptoStore::has_keys(keystore, &public_keys))
+			.await
+	}
+
+	async fn sign_with(
+		&self,
+		id: KeyTypeId,
+		key: &CryptoTypePublicPair,
+		msg: &[u8],
+	) -> Result<Option<Vec<u8>>, Error> {
+		let (key, msg) = (key.clone(), msg.to_vec());
+		self.unblock(move |keystore| SyncCryptoStore::sign_with(keystore, id, &key, &msg))
+			.await
+	}
+
+	async fn sr25519_vrf_sign(
+		&self,
+		key_type: KeyTypeId,
+		public: &sr25519::Public,
+		transcript_data: VRFTranscriptData,
+	) -> Result<Option<VRFSignature>, Error> {
+		SyncCryptoStore::sr25519_vrf_sign(self, key_type, public, transcript_data)
+	}
+
+	async fn ecdsa_sign_prehashed(
+		&self,
+		id: KeyTypeId,
+		public: &ecdsa::Public,
+		msg: &[u8; 32],
+	) -> Result<Option<ecdsa::Signature>, Error> {
+		SyncCryptoStore::ecdsa_sign_prehashed(self, id, public, msg)
+	}
+}
+
+/// A signer serving [`Request`]s from an in-memory keystore.
+///
+/// This is a stand-in for a real signing service: its keys are lost when it stops.
+pub struct LocalSigner {
+	keystore: LocalKeystore,
+}
+
+impl Default for LocalSigner {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl LocalSigner {
+	/// Create a signer without any keys.
+	pub fn new() -> Self {
+		Self { keystore: LocalKeystore::in_memory() }
+	}
+
+	/// Serve requests on a Unix socket at `path` from a background thread.
+	pub fn spawn(self, path: impl AsRef<Path>) -> io::Result<()> {
+		let listener = UnixListener::bind(path)?;
+		std::thread::spawn(move || {
+			for stream in listener.incoming().flatten() {
+				if let Err(e) = self.serve(stream) {
+					log::warn!("Remote keystore signer failed to serve a request: {}", e);
+				}
+			}
+		});
+		Ok(())
+	}
+
+	fn serve(&self, stream: UnixStream) -> io::Result<()> {
+		let mut line = String::new();
+		BufReader::new(&stream).read_line(&mut line)?;
+
+		let response = serde_json::from_str(&line)
+			.map_err(|e| format!("Invalid request: {}", e))
+			.and_then(|request| self.handle(request));
+
+		let mut line = serde_json::to_vec(&response)?;
+		line.push(b'\n');
+		(&stream).write_all(&line)
+	}
+
+	fn handle(&self, request: Request) -> Response {
+		let keystore = &self.keystore;
+		let value = match request {
+			Request::PublicKeys { key_type, scheme } => {
+				let id = key_type_id(&key_type)?;
+				let keys: Vec<Bytes> = match scheme {
+					Scheme::Sr25519 => SyncCryptoStore::sr25519_public_keys(keystore, id)
+						.iter()
+						.map(|public| public.0.to_vec().into())
+						.collect(),
+					Scheme::Ed25519 => SyncCryptoStore::ed25519_public_keys(keystore, id)
+						.iter()
+						.map(|public| public.0.to_vec().into())
+						.collect(),
+				};
+				serde_json::to_value(keys)
+			},
+			Request::GenerateNew { key_type, scheme, seed } => {
+				let id = key_type_id(&key_type)?;
+				let seed = seed.as_deref();
+				let public: Bytes = match scheme {
+					Scheme::Sr25519 => SyncCryptoStore::sr25519_generate_new(keystore, id, seed)
+						.map(|public| public.0.to_vec().into()),
+					Scheme::Ed25519 => SyncCryptoStore::ed25519_generate_new(keystore, id, seed)
+						.map(|public| public.0.to_vec().into()),
+				}
+				.map_err(|e| e.to_string())?;
+				serde_json::to_value(public)
+			},
+			Request::InsertUnknown { key_type, suri, public } => {
+				let id = key_type_id(&key_type)?;
+				SyncCryptoStore::insert_unknown(keystore, id, &suri, &public)
+					.map_err(|()| "Cannot insert key".to_string())?;
+				serde_json::to_value(())
+			},
+			Request::HasKeys { keys } => {
+				let keys = keys
+					.into_iter()
+					.map(|(public, key_type)| Ok((public.0, key_type_id(&key_type)?)))
+					.collect::<Result<Vec<_>, String>>()?;
+				serde_json::to_value(SyncCryptoStore::has_keys(keystore, &keys))
+			},
+			Request::Sign { key_type, scheme, public, message } => {
+				let id = key_type_id(&key_type)?;
+				let key = CryptoTypePublicPair(scheme.crypto_id(), public.0);
+				let signature: Option<Bytes> =
+					SyncCryptoStore::sign_with(keystore, id, &key, &message)
+						.map_err(|e| e.to_string())?
+						.map(Into::into);
+				serde_json::to_value(signature)
+			},
+		};
+
+		value.map_err(|e| e.to_string())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::Pair;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	const KEY_TYPE: KeyTypeId = KeyTypeId(*b"test");
+
+	fn spawn_signer() -> RemoteKeystore {
+		static COUNTER: AtomicUsize = AtomicUsize::new(0);
+		let path = std::env::temp_dir().join(format!(
+			"node-template-signer-{}-{}.sock",
+			std::process::id(),
+			COUNTER.fetch_add(1, Ordering::SeqCst)
+		));
+		let _ = std::fs::remove_file(&path);
+		LocalSigner::new().spawn(&path).unwrap();
+
+		RemoteKeystore::open(&format!("unix://{}", path.display())).unwrap()
+	}
+
+	#[test]
+	fn sr25519_keys_sign_remotely() {
+		let keystore = spawn_signer();
+
+		let public = SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None).unwrap();
+		assert_eq!(SyncCryptoStore::sr25519_public_keys(&keystore, KEY_TYPE), vec![public]);
+		assert!(SyncCryptoStore::has_keys(&keystore, &[(public.0.to_vec(), KEY_TYPE)]));
+
+		let signature = SyncCryptoStore::sign_with(&keystore, KEY_TYPE, &public.into(), b"msg")
+			.unwrap()
+			.unwrap();
+		let signature = sr25519::Signature::try_from(&signature[..]).unwrap();
+		assert!(sr25519::Pair::verify(&signature, b"msg", &public));
+	}
+
+	#[test]
+	fn ed25519_keys_sign_remotely() {
+		let keystore = spawn_signer();
+
+		let public =
+			SyncCryptoStore::ed25519_generate_new(&keystore, KEY_TYPE, Some("//Alice")).unwrap();
+		assert_eq!(public, ed25519::Pair::from_string("//Alice", None).unwrap().public());
+
+		let signature = SyncCryptoStore::sign_with(&keystore, KEY_TYPE, &public.into(), b"msg")
+			.unwrap()
+			.unwrap();
+		let signature = ed25519::Signature::try_from(&signature[..]).unwrap();
+		assert!(ed25519::Pair::verify(&signature, b"msg", &public));
+	}
+
+	#[test]
+	fn unknown_keys_are_not_used() {
+		let keystore = spawn_signer();
+		let public = sr25519::Pair::from_string("//Bob", None).unwrap().public();
+
+		assert!(!SyncCryptoStore::has_keys(&keystore, &[(public.0.to_vec(), KEY_TYPE)]));
+		assert_eq!(
+			SyncCryptoStore::sign_with(&keystore, KEY_TYPE, &public.into(), b"msg").unwrap(),
+			None
+		);
+		assert!(SyncCryptoStore::supported_keys(&keystore, KEY_TYPE, vec![public.into()])
+			.unwrap()
+			.is_empty());
+	}
+
+	#[test]
+	fn inserted_keys_sign_remotely() {
+		let keystore = spawn_signer();
+		let public = sr25519::Pair::from_string("//Charlie", None).unwrap().public();
+
+		SyncCryptoStore::insert_unknown(&keystore, KEY_TYPE, "//Charlie", &public.0).unwrap();
+
+		assert_eq!(SyncCryptoStore::sr25519_public_keys(&keystore, KEY_TYPE), vec![public]);
+	}
+
+	#[test]
+	fn hung_signer_times_out() {
+		let path = std::env::temp_dir()
+			.join(format!("node-template-hung-signer-{}.sock", std::process::id()));
+		let _ = std::fs::remove_file(&path);
+		// Accepts connections but never answers them.
+		let listener = UnixListener::bind(&path).unwrap();
+		std::thread::spawn(move || {
+			let _streams: Vec<_> = listener.incoming().collect();
+		});
+		let keystore = RemoteKeystore::open(&format!("unix://{}", path.display())).unwrap();
+
+		let started = std::time::Instant::now();
+		assert!(SyncCryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None).is_err());
+		assert!(SyncCryptoStore::sr25519_public_keys(&keystore, KEY_TYPE).is_empty());
+		assert!(started.elapsed() < TIMEOUT * 3);
+	}
+
+	#[test]
+	fn async_calls_sign_remotely() {
+		let keystore = spawn_signer();
+
+		let public = futures::executor::block_on(async {
+			let public = CryptoStore::sr25519_generate_new(&keystore, KEY_TYPE, None).await;
+			let public = public.unwrap();
+			let signature =
+				CryptoStore::sign_with(&keystore, KEY_TYPE, &public.into(), b"msg").await;
+			assert!(signature.unwrap().is_some());
+			public
+		});
+		assert_eq!(SyncCryptoStore::sr25519_public_keys(&keystore, KEY_TYPE), vec![public]);
+	}
+
+	#[test]
+	fn connecting_to_a_stalled_signer_times_out() {
+		let path = std::env::temp_dir()
+			.join(format!("node-template-stalled-signer-{}.sock", std::process::id()));
+		let _ = std::fs::remove_file(&path);
+		// Never accepts connections, so they block once its backlog is full.
+		let _listener = UnixListener::bind(&path).unwrap();
+
+		let mut streams = Vec::new();
+		let error = loop {
+			let started = std::time::Instant::now();
+			match connect(&path) {
+				Ok(stream) => streams.push(stream),
+				Err(e) => break (e, started.elapsed()),
+			}
+			assert!(streams.len() < 10_000, "the backlog of the signer never filled up");
+		};
+		assert_eq!(error.0.kind(), io::ErrorKind::TimedOut);
+		assert!(error.1 < TIMEOUT * 2);
+	}
+
+	#[test]
+	fn only_unix_sockets_are_supported() {
+		assert!(RemoteKeystore::open("http://localhost:9000").is_err());
+	}
+}
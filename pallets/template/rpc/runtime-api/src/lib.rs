@@ -0,0 +1,18 @@
+//! Runtime API definition for pallet-template.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+	/// API to read pallet-template state without decoding raw storage.
+	pub trait TemplateApi<AccountId> where
+		AccountId: Codec,
+	{
+		/// The current value of `Something`, if set.
+		fn something() -> Option<u32>;
+
+		/// The value stored under `key` in `who`'s registry, if any.
+		fn registry_value(who: AccountId, key: u32) -> Option<u32>;
+	}
+}
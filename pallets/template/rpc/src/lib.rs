@@ -0,0 +1,365 @@
+//! RPC interface for pallet-template.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+pub use pallet_template_rpc_runtime_api::TemplateApi as TemplateRuntimeApi;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT, NumberFor, Zero},
+};
+
+/// The most blocks `template_somethingHistory` walks back in a single call.
+pub const MAX_HISTORY_LENGTH: u32 = 256;
+
+/// The value of `Something` at a given block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueAt<BlockHash, BlockNumber> {
+	/// Hash of the block.
+	pub block_hash: BlockHash,
+	/// Number of the block.
+	pub block_number: BlockNumber,
+	/// The value of `Something` in the state of that block.
+	pub value: Option<u32>,
+}
+
+#[rpc]
+pub trait TemplateApi<BlockHash, BlockNumber, AccountId> {
+	/// The current value of `Something` at the given block, or the best block.
+	#[rpc(name = "template_something")]
+	fn something(&self, at: Option<BlockHash>) -> Result<Option<u32>>;
+
+	/// The value of `Something` at the given block, or the best block, and at up to `count - 1`
+	/// of its ancestors, newest first.
+	#[rpc(name = "template_somethingHistory")]
+	fn something_history(
+		&self,
+		count: u32,
+		at: Option<BlockHash>,
+	) -> Result<Vec<ValueAt<BlockHash, BlockNumber>>>;
+
+	/// The value stored under `key` in `who`'s registry at the given block, or the best block.
+	#[rpc(name = "template_registryValue")]
+	fn registry_value(
+		&self,
+		who: AccountId,
+		key: u32,
+		at: Option<BlockHash>,
+	) -> Result<Option<u32>>;
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to the runtime failed.
+	RuntimeError,
+	/// The requested block is not known.
+	UnknownBlock,
+	/// The request parameters are out of range.
+	InvalidParams,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+			Error::UnknownBlock => 2,
+			Error::InvalidParams => 3,
+		}
+	}
+}
+
+/// A struct that implements the [`TemplateApi`].
+pub struct Template<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Template<C, B> {
+	/// Create new `Template` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Unable to query pallet-template state.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+fn unknown_block(hash: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::UnknownBlock.into()),
+		message: "Unknown block.".into(),
+		data: Some(format!("{:?}", hash).into()),
+	}
+}
+
+impl<C, Block, AccountId> TemplateApi<<Block as BlockT>::Hash, NumberFor<Block>, AccountId>
+	for Template<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: TemplateRuntimeApi<Block, AccountId>,
+	AccountId: Codec,
+{
+	fn something(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Option<u32>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.something(&at).map_err(runtime_error)
+	}
+
+	fn something_history(
+		&self,
+		count: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Vec<ValueAt<<Block as BlockT>::Hash, NumberFor<Block>>>> {
+		if count > MAX_HISTORY_LENGTH {
+			return Err(RpcError {
+				code: ErrorCode::ServerError(Error::InvalidParams.into()),
+				message: format!("At most {} blocks can be queried at once.", MAX_HISTORY_LENGTH),
+				data: None,
+			})
+		}
+
+		let mut hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		let mut history = Vec::with_capacity(count as usize);
+		for _ in 0..count {
+			let header = self.client.header(BlockId::hash(hash)).map_err(runtime_error)?;
+			let header = header.ok_or_else(|| unknown_block(hash))?;
+			let value = self
+				.client
+				.runtime_api()
+				.something(&BlockId::hash(hash))
+				.map_err(runtime_error)?;
+			history.push(ValueAt { block_hash: hash, block_number: *header.number(), value });
+
+			if header.number().is_zero() {
+				break
+			}
+			hash = *header.parent_hash();
+		}
+
+		Ok(history)
+	}
+
+	fn registry_value(
+		&self,
+		who: AccountId,
+		key: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<u32>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.registry_value(&at, who, key).map_err(runtime_error)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use jsonrpc_core::{
+		serde_json::{self, json, Value},
+		IoHandler,
+	};
+	use sp_api::{ApiError, ApiRef, NativeOrEncoded};
+	use sp_blockchain::{BlockStatus, Info};
+	use sp_runtime::testing::{Block as TestBlock, ExtrinsicWrapper, Header};
+
+	type Block = TestBlock<ExtrinsicWrapper<u64>>;
+
+	/// Blocks #0 to #2, the best block.
+	fn chain() -> Vec<Header> {
+		let mut headers = vec![Header::new_from_number(0)];
+		for number in 1..=2 {
+			let parent_hash = headers.last().unwrap().hash();
+			headers.push(Header { parent_hash, ..Header::new_from_number(number) });
+		}
+		headers
+	}
+
+	fn find<'a>(headers: &'a [Header], id: &BlockId<Block>) -> Option<&'a Header> {
+		headers.iter().find(|header| match id {
+			BlockId::Hash(hash) => header.hash() == *hash,
+			BlockId::Number(number) => header.number == *number,
+		})
+	}
+
+	/// A client of the `chain()`, whose runtime has `Something` set to ten times the block number
+	/// from block #1 on, and the block number stored under key 7 in the registry of account 1.
+	struct TestClient {
+		headers: Vec<Header>,
+	}
+
+	struct RuntimeApi {
+		headers: Vec<Header>,
+	}
+
+	impl ProvideRuntimeApi<Block> for TestClient {
+		type Api = RuntimeApi;
+
+		fn runtime_api<'a>(&'a self) -> ApiRef<'a, Self::Api> {
+			RuntimeApi { headers: self.headers.clone() }.into()
+		}
+	}
+
+	impl HeaderBackend<Block> for TestClient {
+		fn header(
+			&self,
+			id: BlockId<Block>,
+		) -> sp_blockchain::Result<Option<<Block as BlockT>::Header>> {
+			Ok(find(&self.headers, &id).cloned())
+		}
+
+		fn info(&self) -> Info<Block> {
+			let (genesis, best) = (&self.headers[0], self.headers.last().unwrap());
+			Info {
+				best_hash: best.hash(),
+				best_number: best.number,
+				genesis_hash: genesis.hash(),
+				finalized_hash: genesis.hash(),
+				finalized_number: genesis.number,
+				finalized_state: None,
+				number_leaves: 1,
+				block_gap: None,
+			}
+		}
+
+		fn status(&self, id: BlockId<Block>) -> sp_blockchain::Result<BlockStatus> {
+			Ok(match find(&self.headers, &id) {
+				Some(_) => BlockStatus::InChain,
+				None => BlockStatus::Unknown,
+			})
+		}
+
+		fn number(
+			&self,
+			hash: <Block as BlockT>::Hash,
+		) -> sp_blockchain::Result<Option<NumberFor<Block>>> {
+			Ok(find(&self.headers, &BlockId::Hash(hash)).map(|header| header.number))
+		}
+
+		fn hash(
+			&self,
+			number: NumberFor<Block>,
+		) -> sp_blockchain::Result<Option<<Block as BlockT>::Hash>> {
+			Ok(find(&self.headers, &BlockId::Number(number)).map(|header| header.hash()))
+		}
+	}
+
+	impl RuntimeApi {
+		fn number_at(&self, at: &BlockId<Block>) -> std::result::Result<u32, ApiError> {
+			let header = find(&self.headers, at).ok_or_else(|| {
+				let error: Box<dyn std::error::Error + Send + Sync> = "Unknown block".into();
+				ApiError::from(error)
+			})?;
+			Ok(header.number as u32)
+		}
+	}
+
+	sp_api::mock_impl_runtime_apis! {
+		impl TemplateRuntimeApi<Block, u64> for RuntimeApi {
+			#[advanced]
+			fn something(
+				&self,
+				at: &BlockId<Block>,
+			) -> std::result::Result<NativeOrEncoded<Option<u32>>, ApiError> {
+				let number = self.number_at(at)?;
+				Ok(Some(number * 10).filter(|_| number > 0).into())
+			}
+
+			#[advanced]
+			fn registry_value(
+				&self,
+				at: &BlockId<Block>,
+				who: u64,
+				key: u32,
+			) -> std::result::Result<NativeOrEncoded<Option<u32>>, ApiError> {
+				let number = self.number_at(at)?;
+				Ok(Some(number).filter(|_| (who, key) == (1, 7)).into())
+			}
+		}
+	}
+
+	/// Call `method` with `params` and return the `result`, or the `error` of the response.
+	fn call(method: &str, params: Value) -> Value {
+		let mut io = IoHandler::new();
+		let client = Arc::new(TestClient { headers: chain() });
+		io.extend_with(TemplateApi::to_delegate(Template::<_, Block>::new(client)));
+
+		let request = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+		let response = io.handle_request_sync(&request.to_string()).unwrap();
+		let response: Value = serde_json::from_str(&response).unwrap();
+		response.get("result").unwrap_or(&response["error"]).clone()
+	}
+
+	fn hash(number: usize) -> Value {
+		json!(format!("{:?}", chain()[number].hash()))
+	}
+
+	#[test]
+	fn something_defaults_to_best_block() {
+		assert_eq!(call("template_something", json!([])), json!(20));
+		assert_eq!(call("template_something", json!([hash(1)])), json!(10));
+		assert_eq!(call("template_something", json!([hash(0)])), Value::Null);
+	}
+
+	#[test]
+	fn registry_value_is_read_at_the_given_block() {
+		assert_eq!(call("template_registryValue", json!([1, 7])), json!(2));
+		assert_eq!(call("template_registryValue", json!([1, 7, hash(1)])), json!(1));
+		assert_eq!(call("template_registryValue", json!([1, 8])), Value::Null);
+		assert_eq!(call("template_registryValue", json!([2, 7])), Value::Null);
+	}
+
+	#[test]
+	fn something_history_walks_back_count_blocks() {
+		assert_eq!(
+			call("template_somethingHistory", json!([2])),
+			json!([
+				{ "blockHash": hash(2), "blockNumber": 2, "value": 20 },
+				{ "blockHash": hash(1), "blockNumber": 1, "value": 10 },
+			])
+		);
+		assert_eq!(
+			call("template_somethingHistory", json!([1, hash(1)])),
+			json!([{ "blockHash": hash(1), "blockNumber": 1, "value": 10 }])
+		);
+		assert_eq!(call("template_somethingHistory", json!([0])), json!([]));
+	}
+
+	#[test]
+	fn something_history_stops_at_genesis() {
+		let history = call("template_somethingHistory", json!([MAX_HISTORY_LENGTH, hash(1)]));
+		assert_eq!(
+			history,
+			json!([
+				{ "blockHash": hash(1), "blockNumber": 1, "value": 10 },
+				{ "blockHash": hash(0), "blockNumber": 0, "value": null },
+			])
+		);
+	}
+
+	#[test]
+	fn something_history_is_limited() {
+		let error = call("template_somethingHistory", json!([MAX_HISTORY_LENGTH + 1]));
+		assert_eq!(error["code"], json!(3));
+	}
+
+	#[test]
+	fn something_history_of_unknown_block_is_an_error() {
+		let unknown = json!(format!("{:?}", Header::new_from_number(7).hash()));
+		let error = call("template_somethingHistory", json!([1, unknown]));
+		assert_eq!(error["code"], json!(2));
+	}
+}
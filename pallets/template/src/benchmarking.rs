@@ -4,8 +4,17 @@ use super::*;
 
 #[allow(unused)]
 use crate::Pallet as Template;
-use frame_benchmarking::{benchmarks, whitelisted_caller};
+use codec::Decode;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::traits::Currency;
 use frame_system::RawOrigin;
+use sp_runtime::traits::TrailingZeroInput;
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, 0);
+	T::Currency::make_free_balance_be(&who, T::ValueDeposit::get() * 100u32.into());
+	who
+}
 
 benchmarks! {
 	do_something {
@@ -16,5 +25,71 @@ benchmarks! {
 		assert_eq!(Something::<T>::get(), Some(s));
 	}
 
+	cause_error_none {
+		let caller: T::AccountId = whitelisted_caller();
+	}: {
+		assert!(Template::<T>::cause_error(RawOrigin::Signed(caller).into()).is_err());
+	}
+	verify {
+		assert_eq!(Something::<T>::get(), None);
+	}
+
+	cause_error_increment {
+		let caller: T::AccountId = whitelisted_caller();
+		Something::<T>::put(42);
+	}: cause_error(RawOrigin::Signed(caller))
+	verify {
+		assert_eq!(Something::<T>::get(), Some(43));
+	}
+
+	set_value {
+		let caller = funded_account::<T>("caller", 0);
+	}: _(RawOrigin::Signed(caller.clone()), 7, 42)
+	verify {
+		assert_eq!(Template::<T>::value_of(&caller, 7), Some(42));
+	}
+
+	clear_value {
+		let caller = funded_account::<T>("caller", 0);
+		Template::<T>::set_value(RawOrigin::Signed(caller.clone()).into(), 7, 42)?;
+	}: _(RawOrigin::Signed(caller.clone()), 7)
+	verify {
+		assert_eq!(Template::<T>::value_of(&caller, 7), None);
+	}
+
+	transfer_value {
+		let caller = funded_account::<T>("caller", 0);
+		let dest = funded_account::<T>("dest", 0);
+		Template::<T>::set_value(RawOrigin::Signed(caller.clone()).into(), 7, 42)?;
+	}: _(RawOrigin::Signed(caller.clone()), 7, dest.clone())
+	verify {
+		assert_eq!(Template::<T>::value_of(&caller, 7), None);
+		assert_eq!(Template::<T>::value_of(&dest, 7), Some(42));
+	}
+
+	submit_value_signed {
+		let caller: T::AccountId = whitelisted_caller();
+	}: _(RawOrigin::Signed(caller), 42)
+	verify {
+		assert_eq!(Something::<T>::get(), Some(42));
+	}
+
+	submit_value_unsigned {
+		// The payload signature is only checked in `validate_unsigned`, so any well-formed
+		// public key and signature will do here.
+		let public = T::Public::decode(&mut TrailingZeroInput::new(&[][..]))
+			.map_err(|_| "Public key must decode from zeroes")?;
+		let signature = T::Signature::decode(&mut TrailingZeroInput::new(&[][..]))
+			.map_err(|_| "Signature must decode from zeroes")?;
+		let payload = ValuePayload {
+			block_number: frame_system::Pallet::<T>::block_number(),
+			value: 42,
+			public,
+		};
+	}: _(RawOrigin::None, payload, signature)
+	verify {
+		assert_eq!(Something::<T>::get(), Some(42));
+	}
+
 	impl_benchmark_test_suite!(Template, crate::mock::new_test_ext(), crate::mock::Test);
 }
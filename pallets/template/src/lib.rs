@@ -13,21 +13,130 @@ mod tests;
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+pub mod migrations;
+pub mod weights;
+pub use weights::WeightInfo;
+
+use sp_core::crypto::KeyTypeId;
+
+/// Key type of the keys the offchain worker signs its submissions with.
+///
+/// The node needs a key of this type in its keystore before the worker can submit anything,
+/// e.g. via the `author_insertKey` RPC with key type `tmpl`.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"tmpl");
+
+/// Application crypto used by the offchain worker to sign transactions and payloads.
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		MultiSignature, MultiSigner,
+	};
+	app_crypto!(sr25519, KEY_TYPE);
+
+	/// Authority identifier to plug into `Config::AuthorityId` for runtimes using
+	/// `MultiSignature`.
+	pub struct TemplateAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for TemplateAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
 
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::pallet_prelude::*;
-	use frame_system::pallet_prelude::*;
+	use frame_support::{
+		pallet_prelude::*,
+		traits::{BalanceStatus, Contains, Currency, ReservableCurrency, StorageVersion},
+	};
+	use frame_system::{
+		offchain::{
+			AppCrypto, CreateSignedTransaction, SendSignedTransaction, SendUnsignedTransaction,
+			SignedPayload, Signer, SigningTypes,
+		},
+		pallet_prelude::*,
+	};
+	use sp_runtime::traits::{IdentifyAccount, SaturatedConversion, Saturating};
+	use sp_std::vec::Vec;
+
+	use crate::WeightInfo;
+
+	const NO_LOCAL_ACCOUNTS: &str =
+		"No local accounts available. Consider adding one via `author_insertKey` RPC.";
+
+	pub type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+	/// A value held in the registry, together with the deposit reserved for storing it.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct RegistryEntry<Balance> {
+		/// The stored value.
+		pub value: u32,
+		/// The amount reserved from the owner while this entry exists.
+		pub deposit: Balance,
+	}
+
+	/// Payload of an unsigned `submit_value_unsigned` transaction, signed by an offchain worker
+	/// key so that the submission can be verified in `validate_unsigned`.
+	#[derive(Clone, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+	pub struct ValuePayload<Public, BlockNumber> {
+		/// The block at which the value was computed.
+		pub block_number: BlockNumber,
+		/// The computed value.
+		pub value: u32,
+		/// The key that signed this payload.
+		pub public: Public,
+	}
+
+	impl<T: SigningTypes> SignedPayload<T> for ValuePayload<T::Public, T::BlockNumber> {
+		fn public(&self) -> T::Public {
+			self.public.clone()
+		}
+	}
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The identifier type for the offchain worker's signing keys.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+		/// The accounts whose keys may sign the payloads of unsigned submissions. Unsigned
+		/// transactions pay no fees, so an oracle restricts this to its feeders, e.g. the
+		/// validators.
+		type Submitters: Contains<Self::AccountId>;
+
+		/// Priority of the unsigned transactions submitted by the offchain worker.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+
+		/// Minimum number of blocks between two accepted unsigned submissions. Also used as the
+		/// longevity of an unsigned submission in the transaction pool.
+		#[pallet::constant]
+		type UnsignedInterval: Get<Self::BlockNumber>;
+
+		/// The currency in which registry deposits are held.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// The amount reserved from an account for each value it keeps in the registry.
+		#[pallet::constant]
+		type ValueDeposit: Get<BalanceOf<Self>>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
+	/// The current storage version. Bump this and add a module to `migrations` whenever the
+	/// layout of the pallet's storage changes.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	// The pallet's runtime storage items.
@@ -38,6 +147,59 @@ pub mod pallet {
 	// https://docs.substrate.io/v3/runtime/storage#declaring-storage-items
 	pub type Something<T> = StorageValue<_, u32>;
 
+	/// The first block at which the next unsigned submission will be accepted.
+	#[pallet::storage]
+	#[pallet::getter(fn next_unsigned_at)]
+	pub type NextUnsignedAt<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	/// Per-account registry of typed values, keyed by the owning account and a `u32` key.
+	#[pallet::storage]
+	#[pallet::getter(fn registry)]
+	pub type Registry<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		u32,
+		RegistryEntry<BalanceOf<T>>,
+		OptionQuery,
+	>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The initial value of `Something`, if any.
+		pub something: Option<u32>,
+		/// Initial registry entries as `(owner, key, value)`. A deposit is reserved from each
+		/// owner, so they must be endowed by `pallet_balances` genesis.
+		pub registry: Vec<(T::AccountId, u32, u32)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { something: None, registry: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			if let Some(something) = self.something {
+				<Something<T>>::put(something);
+			}
+			for (who, key, value) in &self.registry {
+				assert!(
+					!<Registry<T>>::contains_key(who, key),
+					"Duplicate registry entry in genesis."
+				);
+				let deposit = T::ValueDeposit::get();
+				T::Currency::reserve(who, deposit)
+					.expect("Registry owners must be able to cover the deposit in genesis.");
+				<Registry<T>>::insert(who, key, RegistryEntry { value: *value, deposit });
+			}
+		}
+	}
+
 	// Pallets use events to inform users when important changes are made.
 	// https://docs.substrate.io/v3/runtime/events-and-errors
 	#[pallet::event]
@@ -46,6 +208,15 @@ pub mod pallet {
 		/// Event documentation should end with an array that provides descriptive names for event
 		/// parameters. [something, who]
 		SomethingStored(u32, T::AccountId),
+		/// A value was set in the registry. [who, key, value]
+		ValueSet(T::AccountId, u32, u32),
+		/// A value was removed from the registry. [who, key]
+		ValueCleared(T::AccountId, u32),
+		/// A value was moved to another account. [from, to, key]
+		ValueTransferred(T::AccountId, T::AccountId, u32),
+		/// The offchain worker submitted a value. The submitter is `None` for unsigned
+		/// submissions. [value, who]
+		ValueSubmitted(u32, Option<T::AccountId>),
 	}
 
 	// Errors inform users that something went wrong.
@@ -55,6 +226,51 @@ pub mod pallet {
 		NoneValue,
 		/// Errors should have helpful documentation associated with them.
 		StorageOverflow,
+		/// There is no value stored under the given key for this account.
+		NoSuchValue,
+		/// The destination account already has a value stored under the given key.
+		ValueAlreadyExists,
+		/// A value cannot be transferred to its current owner.
+		TransferToSelf,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::migrate::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			crate::migrations::pre_upgrade::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			crate::migrations::post_upgrade::<T>()
+		}
+
+		/// Compute a value off-chain and submit it back to the chain.
+		///
+		/// Even blocks submit a signed transaction, odd blocks an unsigned one carrying a signed
+		/// payload. Both need a `KEY_TYPE` key in the node's keystore.
+		fn offchain_worker(block_number: T::BlockNumber) {
+			let value = Self::compute_value(block_number);
+
+			let res = if block_number.saturated_into::<u64>() % 2 == 0 {
+				Self::submit_signed(value)
+			} else {
+				Self::submit_unsigned(block_number, value)
+			};
+
+			match res {
+				Ok(()) => {},
+				// Nodes without a key simply do not take part.
+				Err(NO_LOCAL_ACCOUNTS) =>
+					log::debug!(target: "runtime::template", "Offchain worker: {}", NO_LOCAL_ACCOUNTS),
+				Err(e) => log::error!(target: "runtime::template", "Offchain worker error: {}", e),
+			}
+		}
 	}
 
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
@@ -64,7 +280,7 @@ pub mod pallet {
 	impl<T: Config> Pallet<T> {
 		/// An example dispatchable that takes a singles value as a parameter, writes the value to
 		/// storage and emits an event. This function must be dispatched by a signed extrinsic.
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		#[pallet::weight(T::WeightInfo::do_something(*something))]
 		pub fn do_something(origin: OriginFor<T>, something: u32) -> DispatchResult {
 			// Check that the extrinsic was signed and get the signer.
 			// This function will return an error if the extrinsic is not signed.
@@ -81,7 +297,9 @@ pub mod pallet {
 		}
 
 		/// An example dispatchable that may throw a custom error.
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1))]
+		#[pallet::weight(
+			T::WeightInfo::cause_error_none().max(T::WeightInfo::cause_error_increment())
+		)]
 		pub fn cause_error(origin: OriginFor<T>) -> DispatchResult {
 			let _who = ensure_signed(origin)?;
 
@@ -98,5 +316,182 @@ pub mod pallet {
 				},
 			}
 		}
+
+		/// Store `value` under `key` in the caller's registry, overwriting any previous value.
+		///
+		/// Creating a new entry reserves `ValueDeposit` from the caller; overwriting an existing
+		/// entry keeps the deposit that was already reserved for it.
+		#[pallet::weight(T::WeightInfo::set_value())]
+		pub fn set_value(origin: OriginFor<T>, key: u32, value: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let deposit = match <Registry<T>>::get(&who, key) {
+				Some(entry) => entry.deposit,
+				None => {
+					let deposit = T::ValueDeposit::get();
+					T::Currency::reserve(&who, deposit)?;
+					deposit
+				},
+			};
+			<Registry<T>>::insert(&who, key, RegistryEntry { value, deposit });
+
+			Self::deposit_event(Event::ValueSet(who, key, value));
+			Ok(())
+		}
+
+		/// Remove the value stored under `key` in the caller's registry and release its deposit.
+		#[pallet::weight(T::WeightInfo::clear_value())]
+		pub fn clear_value(origin: OriginFor<T>, key: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let entry = <Registry<T>>::take(&who, key).ok_or(Error::<T>::NoSuchValue)?;
+			let _ = T::Currency::unreserve(&who, entry.deposit);
+
+			Self::deposit_event(Event::ValueCleared(who, key));
+			Ok(())
+		}
+
+		/// Move the value stored under `key` from the caller's registry to `dest`'s registry.
+		///
+		/// The deposit moves with the entry and stays reserved on `dest`, who gets it back when
+		/// the entry is cleared. Fails if `dest` already holds a value under the same key.
+		#[pallet::weight(T::WeightInfo::transfer_value())]
+		pub fn transfer_value(
+			origin: OriginFor<T>,
+			key: u32,
+			dest: T::AccountId,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			ensure!(who != dest, Error::<T>::TransferToSelf);
+			ensure!(!<Registry<T>>::contains_key(&dest, key), Error::<T>::ValueAlreadyExists);
+			let entry = <Registry<T>>::get(&who, key).ok_or(Error::<T>::NoSuchValue)?;
+			let remaining = T::Currency::repatriate_reserved(
+				&who,
+				&dest,
+				entry.deposit,
+				BalanceStatus::Reserved,
+			)?;
+			// Part of the deposit may have been slashed, so only record what actually moved.
+			let deposit = entry.deposit.saturating_sub(remaining);
+			<Registry<T>>::remove(&who, key);
+			<Registry<T>>::insert(&dest, key, RegistryEntry { deposit, ..entry });
+
+			Self::deposit_event(Event::ValueTransferred(who, dest, key));
+			Ok(())
+		}
+
+		/// Store a value computed by the offchain worker, submitted as a signed transaction.
+		#[pallet::weight(T::WeightInfo::submit_value_signed())]
+		pub fn submit_value_signed(origin: OriginFor<T>, value: u32) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			<Something<T>>::put(value);
+
+			Self::deposit_event(Event::ValueSubmitted(value, Some(who)));
+			Ok(())
+		}
+
+		/// Store a value computed by the offchain worker, submitted as an unsigned transaction.
+		///
+		/// The payload signature is checked in `validate_unsigned`, so it is not verified again
+		/// here.
+		#[pallet::weight(T::WeightInfo::submit_value_unsigned())]
+		pub fn submit_value_unsigned(
+			origin: OriginFor<T>,
+			payload: ValuePayload<T::Public, T::BlockNumber>,
+			_signature: T::Signature,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			<Something<T>>::put(payload.value);
+			let current_block = <frame_system::Pallet<T>>::block_number();
+			<NextUnsignedAt<T>>::put(current_block + T::UnsignedInterval::get());
+
+			Self::deposit_event(Event::ValueSubmitted(payload.value, None));
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		/// Only accept unsigned submissions signed by one of the `Submitters`, with a valid
+		/// payload signature, that are not ahead of the chain and respect `UnsignedInterval`.
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			if let Call::submit_value_unsigned { payload, signature } = call {
+				if !T::Submitters::contains(&payload.public.clone().into_account()) {
+					return InvalidTransaction::BadSigner.into()
+				}
+				let signature_valid =
+					SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone());
+				if !signature_valid {
+					return InvalidTransaction::BadProof.into()
+				}
+
+				let next_unsigned_at = <NextUnsignedAt<T>>::get();
+				if next_unsigned_at > payload.block_number {
+					return InvalidTransaction::Stale.into()
+				}
+				let current_block = <frame_system::Pallet<T>>::block_number();
+				if current_block < payload.block_number {
+					return InvalidTransaction::Future.into()
+				}
+
+				ValidTransaction::with_tag_prefix("TemplateOffchainWorker")
+					.priority(T::UnsignedPriority::get())
+					// Only one submission per interval can be in the pool.
+					.and_provides(next_unsigned_at)
+					.longevity(T::UnsignedInterval::get().saturated_into::<u64>())
+					.propagate(true)
+					.build()
+			} else {
+				InvalidTransaction::Call.into()
+			}
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The value stored under `key` in `who`'s registry, if any.
+		pub fn value_of(who: &T::AccountId, key: u32) -> Option<u32> {
+			<Registry<T>>::get(who, key).map(|entry| entry.value)
+		}
+
+		/// The off-chain computation. This is where an oracle would fetch its data, e.g. over
+		/// HTTP; here the next value is simply derived from the current one and the block number.
+		pub fn compute_value(block_number: T::BlockNumber) -> u32 {
+			<Something<T>>::get()
+				.unwrap_or_default()
+				.wrapping_add(block_number.saturated_into::<u32>())
+		}
+
+		fn submit_signed(value: u32) -> Result<(), &'static str> {
+			let signer = Signer::<T, T::AuthorityId>::any_account();
+			if !signer.can_sign() {
+				return Err(NO_LOCAL_ACCOUNTS)
+			}
+
+			match signer.send_signed_transaction(|_account| Call::submit_value_signed { value }) {
+				Some((_, Ok(()))) => Ok(()),
+				_ => Err("Failed to submit signed transaction."),
+			}
+		}
+
+		fn submit_unsigned(block_number: T::BlockNumber, value: u32) -> Result<(), &'static str> {
+			if <NextUnsignedAt<T>>::get() > block_number {
+				// Too early; the transaction would be rejected as stale anyway.
+				return Ok(())
+			}
+
+			match Signer::<T, T::AuthorityId>::any_account().send_unsigned_transaction(
+				|account| ValuePayload { block_number, value, public: account.public.clone() },
+				|payload, signature| Call::submit_value_unsigned { payload, signature },
+			) {
+				Some((_, Ok(()))) => Ok(()),
+				Some((_, Err(()))) => Err("Failed to submit unsigned transaction."),
+				None => Err(NO_LOCAL_ACCOUNTS),
+			}
+		}
 	}
 }
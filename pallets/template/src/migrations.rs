@@ -0,0 +1,98 @@
+//! Storage migrations for pallet-template.
+//!
+//! Each storage version that changes the layout gets its own module with a `migrate` function
+//! and, behind the `try-runtime` feature, `pre_migrate` and `post_migrate` checks. The functions
+//! at the top of this module run every migration the on-chain storage version still needs.
+
+use crate::*;
+use frame_support::{
+	traits::{Get, GetStorageVersion},
+	weights::Weight,
+};
+
+/// Run all migrations needed to bring storage from the on-chain version to the current one.
+pub fn migrate<T: Config>() -> Weight {
+	let on_chain = Pallet::<T>::on_chain_storage_version();
+	let mut weight = T::DbWeight::get().reads(1);
+
+	if on_chain < 1 {
+		weight = weight.saturating_add(v1::migrate::<T>());
+	}
+
+	weight
+}
+
+/// Checks to run before `migrate`.
+#[cfg(feature = "try-runtime")]
+pub fn pre_upgrade<T: Config>() -> Result<(), &'static str> {
+	let on_chain = Pallet::<T>::on_chain_storage_version();
+
+	if on_chain < 1 {
+		v1::pre_migrate::<T>()?;
+	}
+
+	Ok(())
+}
+
+/// Checks to run after `migrate`.
+#[cfg(feature = "try-runtime")]
+pub fn post_upgrade<T: Config>() -> Result<(), &'static str> {
+	frame_support::ensure!(
+		Pallet::<T>::on_chain_storage_version() == Pallet::<T>::current_storage_version(),
+		"Storage version was not updated by the migration"
+	);
+
+	v1::post_migrate::<T>()
+}
+
+/// Version 1 stores a `RegistryEntry` with the reserved deposit instead of a bare `u32` value.
+///
+/// Entries written before deposits existed are kept with a zero deposit.
+pub mod v1 {
+	use super::*;
+	use frame_support::traits::StorageVersion;
+	use sp_runtime::traits::Zero;
+
+	/// Translate every `u32` registry value into a `RegistryEntry` and bump the storage version.
+	pub fn migrate<T: Config>() -> Weight {
+		let mut translated = 0u64;
+		Registry::<T>::translate::<u32, _>(|_who, _key, value| {
+			translated += 1;
+			Some(RegistryEntry { value, deposit: Zero::zero() })
+		});
+		StorageVersion::new(1).put::<Pallet<T>>();
+
+		log::info!(target: "runtime::template", "Migrated {} registry entries to v1", translated);
+
+		T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+	}
+
+	/// Every registry value must still be a bare `u32`.
+	#[cfg(feature = "try-runtime")]
+	pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+		use codec::DecodeAll;
+		use frame_support::storage::unhashed;
+
+		for (who, key) in Registry::<T>::iter_keys() {
+			let raw = unhashed::get_raw(&Registry::<T>::hashed_key_for(&who, key))
+				.ok_or("Registry key without a value")?;
+			frame_support::ensure!(
+				<u32 as DecodeAll>::decode_all(&raw).is_ok(),
+				"Registry value is not a v0 `u32`"
+			);
+		}
+
+		Ok(())
+	}
+
+	/// Every registry value must decode as a `RegistryEntry`.
+	#[cfg(feature = "try-runtime")]
+	pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			Registry::<T>::iter_keys().count() == Registry::<T>::iter_values().count(),
+			"Registry contains values that do not decode as `RegistryEntry`"
+		);
+
+		Ok(())
+	}
+}
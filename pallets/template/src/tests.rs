@@ -1,5 +1,19 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{mock::*, Error, Registry, RegistryEntry, Something, ValuePayload};
+use codec::Decode;
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::unhashed,
+	traits::{
+		GetStorageVersion, OffchainWorker, OnRuntimeUpgrade, ReservableCurrency, StorageVersion,
+	},
+	unsigned::ValidateUnsigned,
+};
+use sp_core::offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt};
+use sp_runtime::{
+	testing::UintAuthorityId,
+	transaction_validity::{InvalidTransaction, TransactionSource},
+	BuildStorage, RuntimeAppPublic,
+};
 
 #[test]
 fn it_works_for_default_value() {
@@ -18,3 +32,274 @@ fn correct_error_for_none_value() {
 		assert_noop!(TemplateModule::cause_error(Origin::signed(1)), Error::<Test>::NoneValue);
 	});
 }
+
+#[test]
+fn set_value_is_per_account() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::set_value(Origin::signed(1), 7, 42));
+		assert_ok!(TemplateModule::set_value(Origin::signed(2), 7, 43));
+		// Each account keeps its own value under the same key.
+		assert_eq!(TemplateModule::value_of(&1, 7), Some(42));
+		assert_eq!(TemplateModule::value_of(&2, 7), Some(43));
+		// Overwriting only touches the caller's entry.
+		assert_ok!(TemplateModule::set_value(Origin::signed(1), 7, 44));
+		assert_eq!(TemplateModule::value_of(&1, 7), Some(44));
+		assert_eq!(TemplateModule::value_of(&2, 7), Some(43));
+	});
+}
+
+#[test]
+fn clear_value_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(TemplateModule::clear_value(Origin::signed(1), 7), Error::<Test>::NoSuchValue);
+		assert_ok!(TemplateModule::set_value(Origin::signed(1), 7, 42));
+		assert_ok!(TemplateModule::clear_value(Origin::signed(1), 7));
+		assert_eq!(TemplateModule::value_of(&1, 7), None);
+	});
+}
+
+#[test]
+fn transfer_value_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::transfer_value(Origin::signed(1), 7, 2),
+			Error::<Test>::NoSuchValue
+		);
+		assert_ok!(TemplateModule::set_value(Origin::signed(1), 7, 42));
+		assert_noop!(
+			TemplateModule::transfer_value(Origin::signed(1), 7, 1),
+			Error::<Test>::TransferToSelf
+		);
+		assert_ok!(TemplateModule::set_value(Origin::signed(2), 7, 43));
+		assert_noop!(
+			TemplateModule::transfer_value(Origin::signed(1), 7, 2),
+			Error::<Test>::ValueAlreadyExists
+		);
+		assert_ok!(TemplateModule::transfer_value(Origin::signed(1), 7, 3));
+		assert_eq!(TemplateModule::value_of(&1, 7), None);
+		assert_eq!(TemplateModule::value_of(&3, 7), Some(42));
+	});
+}
+
+#[test]
+fn set_value_reserves_deposit_once() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::set_value(Origin::signed(1), 7, 42));
+		assert_eq!(Balances::reserved_balance(1), 10);
+		assert_eq!(Balances::free_balance(1), 90);
+		// Overwriting an existing entry does not reserve again.
+		assert_ok!(TemplateModule::set_value(Origin::signed(1), 7, 43));
+		assert_eq!(Balances::reserved_balance(1), 10);
+		// Every new key costs another deposit.
+		assert_ok!(TemplateModule::set_value(Origin::signed(1), 8, 42));
+		assert_eq!(Balances::reserved_balance(1), 20);
+	});
+}
+
+#[test]
+fn set_value_fails_without_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::set_value(Origin::signed(4), 7, 42),
+			pallet_balances::Error::<Test>::InsufficientBalance
+		);
+		assert_eq!(TemplateModule::value_of(&4, 7), None);
+	});
+}
+
+#[test]
+fn clear_value_releases_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::set_value(Origin::signed(1), 7, 42));
+		assert_ok!(TemplateModule::clear_value(Origin::signed(1), 7));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 100);
+	});
+}
+
+#[test]
+fn transfer_value_moves_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::set_value(Origin::signed(1), 7, 42));
+		assert_ok!(TemplateModule::transfer_value(Origin::signed(1), 7, 2));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), 90);
+		assert_eq!(Balances::reserved_balance(2), 10);
+		// The new owner gets the deposit back when clearing the entry.
+		assert_ok!(TemplateModule::clear_value(Origin::signed(2), 7));
+		assert_eq!(Balances::free_balance(2), 110);
+	});
+}
+
+#[test]
+fn transfer_value_records_only_the_deposit_left_after_slashing() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::set_value(Origin::signed(1), 7, 42));
+		let _ = Balances::slash_reserved(&1, 4);
+		assert_ok!(TemplateModule::transfer_value(Origin::signed(1), 7, 2));
+		assert_eq!(Registry::<Test>::get(2, 7), Some(RegistryEntry { value: 42, deposit: 6 }));
+		assert_eq!(Balances::reserved_balance(2), 6);
+		// Clearing the entry releases no more than was moved to the new owner.
+		assert_ok!(TemplateModule::clear_value(Origin::signed(2), 7));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 106);
+	});
+}
+
+#[test]
+fn genesis_config_seeds_storage() {
+	let t = GenesisConfig {
+		balances: BalancesConfig { balances: vec![(1, 100)] },
+		template_module: TemplateModuleConfig { something: Some(42), registry: vec![(1, 7, 43)] },
+		..Default::default()
+	}
+	.build_storage()
+	.unwrap();
+
+	sp_io::TestExternalities::from(t).execute_with(|| {
+		assert_eq!(TemplateModule::something(), Some(42));
+		assert_eq!(TemplateModule::value_of(&1, 7), Some(43));
+		assert_eq!(Balances::reserved_balance(1), 10);
+	});
+}
+
+#[test]
+fn offchain_worker_submits_signed_transaction_on_even_blocks() {
+	let (offchain, _state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let mut t = new_test_ext();
+	t.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	t.register_extension(OffchainDbExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+
+	t.execute_with(|| {
+		UintAuthorityId::set_all_keys(vec![1u64]);
+		Something::<Test>::put(40);
+
+		TemplateModule::offchain_worker(2);
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert!(pool_state.read().transactions.is_empty());
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature.unwrap().0, 0);
+		assert_eq!(tx.call, Call::TemplateModule(crate::Call::submit_value_signed { value: 42 }));
+	});
+}
+
+#[test]
+fn offchain_worker_submits_unsigned_transaction_on_odd_blocks() {
+	let (offchain, _state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+	let mut t = new_test_ext();
+	t.register_extension(OffchainWorkerExt::new(offchain.clone()));
+	t.register_extension(OffchainDbExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+
+	t.execute_with(|| {
+		UintAuthorityId::set_all_keys(vec![1u64]);
+		System::set_block_number(3);
+
+		TemplateModule::offchain_worker(3);
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, None);
+		let payload = ValuePayload { block_number: 3, value: 3, public: UintAuthorityId(1) };
+		let signature = UintAuthorityId(1).sign(&codec::Encode::encode(&payload)).unwrap();
+		let call = crate::Call::submit_value_unsigned {
+			payload: payload.clone(),
+			signature: signature.clone(),
+		};
+		assert_eq!(tx.call, Call::TemplateModule(call.clone()));
+
+		// The submission is valid and respects the configured pool parameters.
+		let valid = TemplateModule::validate_unsigned(TransactionSource::Local, &call).unwrap();
+		assert_eq!(valid.priority, UnsignedPriority::get());
+		assert_eq!(valid.longevity, UnsignedInterval::get());
+
+		// Once applied, further submissions are stale until the interval has passed.
+		assert_ok!(TemplateModule::submit_value_unsigned(Origin::none(), payload, signature));
+		assert_eq!(TemplateModule::something(), Some(3));
+		assert_eq!(TemplateModule::next_unsigned_at(), 7);
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::Local, &call),
+			InvalidTransaction::Stale.into()
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_rejects_bad_submissions() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(3);
+		let payload = ValuePayload { block_number: 3, value: 3, public: UintAuthorityId(1) };
+
+		// Signed by a different key than the one in the payload.
+		let signature = UintAuthorityId(2).sign(&codec::Encode::encode(&payload)).unwrap();
+		let call = crate::Call::submit_value_unsigned { payload: payload.clone(), signature };
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadProof.into()
+		);
+
+		// Computed for a block the chain has not reached yet.
+		let payload = ValuePayload { block_number: 4, ..payload };
+		let signature = UintAuthorityId(1).sign(&codec::Encode::encode(&payload)).unwrap();
+		let call = crate::Call::submit_value_unsigned { payload: payload.clone(), signature };
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Future.into()
+		);
+
+		// Validly signed, but by a key that is not one of the submitters.
+		let payload = ValuePayload { block_number: 3, public: UintAuthorityId(3), ..payload };
+		let signature = UintAuthorityId(3).sign(&codec::Encode::encode(&payload)).unwrap();
+		let call = crate::Call::submit_value_unsigned { payload, signature };
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadSigner.into()
+		);
+	});
+}
+
+#[test]
+fn migration_to_v1_wraps_registry_values() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<TemplateModule>();
+		// Write v0 registry values: a bare `u32` per entry.
+		unhashed::put(&Registry::<Test>::hashed_key_for(&1u64, 7u32), &42u32);
+		unhashed::put(&Registry::<Test>::hashed_key_for(&2u64, 8u32), &43u32);
+
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(crate::migrations::pre_upgrade::<Test>());
+		TemplateModule::on_runtime_upgrade();
+		#[cfg(feature = "try-runtime")]
+		assert_ok!(crate::migrations::post_upgrade::<Test>());
+
+		assert_eq!(TemplateModule::on_chain_storage_version(), 1);
+		assert_eq!(
+			TemplateModule::registry(1u64, 7u32),
+			Some(RegistryEntry { value: 42, deposit: 0 })
+		);
+		assert_eq!(
+			TemplateModule::registry(2u64, 8u32),
+			Some(RegistryEntry { value: 43, deposit: 0 })
+		);
+	});
+}
+
+#[test]
+fn migration_is_noop_on_current_version() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(1).put::<TemplateModule>();
+		assert_ok!(TemplateModule::set_value(Origin::signed(1), 7, 42));
+
+		TemplateModule::on_runtime_upgrade();
+
+		assert_eq!(TemplateModule::on_chain_storage_version(), 1);
+		assert_eq!(
+			TemplateModule::registry(1u64, 7u32),
+			Some(RegistryEntry { value: 42, deposit: 10 })
+		);
+	});
+}
@@ -0,0 +1,143 @@
+//! Placeholder weights for pallet_template.
+//!
+//! THESE VALUES ARE NOT BENCHMARK RESULTS. No benchmark run has produced this file yet. The storage
+//! accesses of each call are listed the way the benchmarks in `benchmarking.rs` report them, and
+//! the base weights are estimates; `do_something` ignores `s` until it is measured. Run the command
+//! below on reference hardware and commit its output in place of this file.
+
+// Command to generate the real weights:
+// ./target/release/node-template
+// benchmark
+// --chain=dev
+// --steps=50
+// --repeat=20
+// --pallet=pallet_template
+// --extrinsic=*
+// --execution=wasm
+// --wasm-execution=compiled
+// --heap-pages=4096
+// --output=./pallets/template/src/weights.rs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_template.
+pub trait WeightInfo {
+	fn do_something(s: u32, ) -> Weight;
+	fn cause_error_none() -> Weight;
+	fn cause_error_increment() -> Weight;
+	fn set_value() -> Weight;
+	fn clear_value() -> Weight;
+	fn transfer_value() -> Weight;
+	fn submit_value_signed() -> Weight;
+	fn submit_value_unsigned() -> Weight;
+}
+
+/// Placeholder weights for pallet_template, until they are benchmarked.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: TemplateModule Something (r:0 w:1)
+	fn do_something(_s: u32, ) -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TemplateModule Something (r:1 w:0)
+	fn cause_error_none() -> Weight {
+		(6_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	// Storage: TemplateModule Something (r:1 w:1)
+	fn cause_error_increment() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TemplateModule Registry (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn set_value() -> Weight {
+		(38_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: TemplateModule Registry (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn clear_value() -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	// Storage: TemplateModule Registry (r:2 w:2)
+	// Storage: System Account (r:2 w:2)
+	fn transfer_value() -> Weight {
+		(59_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	// Storage: TemplateModule Something (r:0 w:1)
+	fn submit_value_signed() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TemplateModule Something (r:0 w:1)
+	// Storage: TemplateModule NextUnsignedAt (r:0 w:1)
+	fn submit_value_unsigned() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	// Storage: TemplateModule Something (r:0 w:1)
+	fn do_something(_s: u32, ) -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TemplateModule Something (r:1 w:0)
+	fn cause_error_none() -> Weight {
+		(6_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	// Storage: TemplateModule Something (r:1 w:1)
+	fn cause_error_increment() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TemplateModule Registry (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn set_value() -> Weight {
+		(38_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: TemplateModule Registry (r:1 w:1)
+	// Storage: System Account (r:1 w:1)
+	fn clear_value() -> Weight {
+		(36_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	// Storage: TemplateModule Registry (r:2 w:2)
+	// Storage: System Account (r:2 w:2)
+	fn transfer_value() -> Weight {
+		(59_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	// Storage: TemplateModule Something (r:0 w:1)
+	fn submit_value_signed() -> Weight {
+		(18_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: TemplateModule Something (r:0 w:1)
+	// Storage: TemplateModule NextUnsignedAt (r:0 w:1)
+	fn submit_value_unsigned() -> Weight {
+		(17_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+}
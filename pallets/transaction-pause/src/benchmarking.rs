@@ -0,0 +1,29 @@
+//! Benchmarking setup for pallet-transaction-pause
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as TransactionPause;
+use frame_benchmarking::benchmarks;
+
+benchmarks! {
+	pause {
+		let origin = T::PauseOrigin::successful_origin();
+		let pallet_name = b"TemplateModule".to_vec();
+		let call_name = Some(b"do_something".to_vec());
+	}: _<T::Origin>(origin, pallet_name, call_name)
+	verify {
+		assert!(TransactionPause::<T>::is_paused("TemplateModule", "do_something"));
+	}
+
+	resume {
+		let key = (b"TemplateModule".to_vec(), Some(b"do_something".to_vec()));
+		Paused::<T>::insert(&key, ());
+		let origin = T::PauseOrigin::successful_origin();
+	}: _<T::Origin>(origin, key.0, key.1)
+	verify {
+		assert!(!TransactionPause::<T>::is_paused("TemplateModule", "do_something"));
+	}
+
+	impl_benchmark_test_suite!(TransactionPause, crate::mock::new_test_ext(), crate::mock::Test);
+}
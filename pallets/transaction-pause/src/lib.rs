@@ -0,0 +1,148 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Pauses and resumes calls of other pallets, as an emergency brake.
+//!
+//! `Config::PauseOrigin` can pause a whole pallet or a single call of it, by the names under which
+//! they appear in `construct_runtime!` and in the metadata, such as `TemplateModule` and
+//! `do_something`. The runtime enforces pauses by using [`NotPaused`] as the `BaseCallFilter` of
+//! `frame_system`, so paused calls are rejected for every origin but root, including inside
+//! batches. This pallet and the pallets in `Config::UnpausablePallets` can never be paused.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::WeightInfo;
+
+use frame_support::{
+	dispatch::{CallMetadata, GetCallMetadata},
+	traits::Contains,
+};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{pallet_prelude::*, traits::PalletInfoAccess};
+	use frame_system::pallet_prelude::*;
+	use sp_std::vec::Vec;
+
+	use crate::WeightInfo;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The origin allowed to pause and resume calls.
+		type PauseOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Names of the pallets that must always stay callable. This pallet is one of them in any
+		/// case.
+		type UnpausablePallets: Contains<Vec<u8>>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The paused pallets, keyed by `(pallet_name, None)`, and the paused calls, keyed by
+	/// `(pallet_name, Some(call_name))`.
+	#[pallet::storage]
+	pub type Paused<T> = StorageMap<_, Blake2_128Concat, (Vec<u8>, Option<Vec<u8>>), ()>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A pallet, or only one of its calls, was paused. [pallet_name, call_name]
+		Paused(Vec<u8>, Option<Vec<u8>>),
+		/// A pallet, or only one of its calls, was resumed. [pallet_name, call_name]
+		Resumed(Vec<u8>, Option<Vec<u8>>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The pallet must always stay callable.
+		Unpausable,
+		/// The pallet or call is already paused.
+		AlreadyPaused,
+		/// The pallet or call is not paused.
+		NotPaused,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Pause the call `call_name` of the pallet `pallet_name`, or all of its calls if
+		/// `call_name` is `None`.
+		#[pallet::weight(T::WeightInfo::pause())]
+		pub fn pause(
+			origin: OriginFor<T>,
+			pallet_name: Vec<u8>,
+			call_name: Option<Vec<u8>>,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+			ensure!(
+				pallet_name != <Self as PalletInfoAccess>::name().as_bytes() &&
+					!T::UnpausablePallets::contains(&pallet_name),
+				Error::<T>::Unpausable
+			);
+
+			let key = (pallet_name, call_name);
+			ensure!(!<Paused<T>>::contains_key(&key), Error::<T>::AlreadyPaused);
+			<Paused<T>>::insert(&key, ());
+
+			Self::deposit_event(Event::Paused(key.0, key.1));
+			Ok(())
+		}
+
+		/// Lift a pause set by `pause` with the same arguments.
+		#[pallet::weight(T::WeightInfo::resume())]
+		pub fn resume(
+			origin: OriginFor<T>,
+			pallet_name: Vec<u8>,
+			call_name: Option<Vec<u8>>,
+		) -> DispatchResult {
+			T::PauseOrigin::ensure_origin(origin)?;
+
+			let key = (pallet_name, call_name);
+			ensure!(<Paused<T>>::take(&key).is_some(), Error::<T>::NotPaused);
+
+			Self::deposit_event(Event::Resumed(key.0, key.1));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Whether the call `call_name` of the pallet `pallet_name` is paused, on its own or with its
+	/// whole pallet.
+	pub fn is_paused(pallet_name: &str, call_name: &str) -> bool {
+		let pallet_name = pallet_name.as_bytes().to_vec();
+		<Paused<T>>::contains_key((pallet_name.clone(), None::<Vec<u8>>)) ||
+			<Paused<T>>::contains_key((pallet_name, Some(call_name.as_bytes().to_vec())))
+	}
+}
+
+/// A call filter letting through all calls that are not paused.
+pub struct NotPaused<T>(PhantomData<T>);
+
+impl<T: Config> Contains<<T as frame_system::Config>::Call> for NotPaused<T>
+where
+	<T as frame_system::Config>::Call: GetCallMetadata,
+{
+	fn contains(call: &<T as frame_system::Config>::Call) -> bool {
+		let CallMetadata { pallet_name, function_name } = call.get_call_metadata();
+		!Pallet::<T>::is_paused(pallet_name, function_name)
+	}
+}
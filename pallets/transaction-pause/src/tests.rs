@@ -0,0 +1,115 @@
+use crate::{mock::*, Error, Event as TransactionPauseEvent, NotPaused};
+use frame_support::{assert_noop, assert_ok, traits::Contains};
+use sp_runtime::{traits::Dispatchable, DispatchError};
+
+fn last_event() -> TransactionPauseEvent<Test> {
+	System::events()
+		.into_iter()
+		.filter_map(|r| if let Event::TransactionPause(e) = r.event { Some(e) } else { None })
+		.last()
+		.unwrap()
+}
+
+fn remark() -> Call {
+	Call::System(frame_system::Call::remark { remark: vec![] })
+}
+
+fn remark_with_event() -> Call {
+	Call::System(frame_system::Call::remark_with_event { remark: vec![] })
+}
+
+#[test]
+fn paused_call_is_filtered() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TransactionPause::pause(
+			Origin::root(),
+			b"System".to_vec(),
+			Some(b"remark".to_vec())
+		));
+		assert_eq!(
+			last_event(),
+			TransactionPauseEvent::Paused(b"System".to_vec(), Some(b"remark".to_vec()))
+		);
+
+		assert!(!NotPaused::<Test>::contains(&remark()));
+		assert!(NotPaused::<Test>::contains(&remark_with_event()));
+		assert!(remark().dispatch(Origin::signed(1)).is_err());
+		// Root is never filtered.
+		assert_ok!(remark().dispatch(Origin::root()));
+	});
+}
+
+#[test]
+fn paused_pallet_filters_all_its_calls() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TransactionPause::pause(Origin::root(), b"System".to_vec(), None));
+
+		assert!(!NotPaused::<Test>::contains(&remark()));
+		assert!(!NotPaused::<Test>::contains(&remark_with_event()));
+	});
+}
+
+#[test]
+fn resumed_call_passes_again() {
+	new_test_ext().execute_with(|| {
+		let call_name = Some(b"remark".to_vec());
+		assert_ok!(TransactionPause::pause(Origin::root(), b"System".to_vec(), call_name.clone()));
+		assert_ok!(TransactionPause::resume(Origin::root(), b"System".to_vec(), call_name.clone()));
+		assert_eq!(last_event(), TransactionPauseEvent::Resumed(b"System".to_vec(), call_name));
+
+		assert!(NotPaused::<Test>::contains(&remark()));
+		assert_ok!(remark().dispatch(Origin::signed(1)));
+	});
+}
+
+#[test]
+fn resuming_a_call_keeps_its_pallet_paused() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TransactionPause::pause(Origin::root(), b"System".to_vec(), None));
+		assert_noop!(
+			TransactionPause::resume(Origin::root(), b"System".to_vec(), Some(b"remark".to_vec())),
+			Error::<Test>::NotPaused
+		);
+
+		assert!(!NotPaused::<Test>::contains(&remark()));
+	});
+}
+
+#[test]
+fn pause_pallet_and_unpausable_pallets_cannot_be_paused() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TransactionPause::pause(Origin::root(), b"TransactionPause".to_vec(), None),
+			Error::<Test>::Unpausable
+		);
+		assert_noop!(
+			TransactionPause::pause(Origin::root(), b"Governance".to_vec(), None),
+			Error::<Test>::Unpausable
+		);
+	});
+}
+
+#[test]
+fn pausing_twice_fails() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TransactionPause::pause(Origin::root(), b"System".to_vec(), None));
+		assert_noop!(
+			TransactionPause::pause(Origin::root(), b"System".to_vec(), None),
+			Error::<Test>::AlreadyPaused
+		);
+	});
+}
+
+#[test]
+fn only_pause_origin_pauses_and_resumes() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TransactionPause::pause(Origin::signed(1), b"System".to_vec(), None),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			TransactionPause::resume(Origin::signed(1), b"System".to_vec(), None),
+			DispatchError::BadOrigin
+		);
+	});
+}
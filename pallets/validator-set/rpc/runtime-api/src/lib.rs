@@ -0,0 +1,44 @@
+//! Runtime API definition for the liveness of the validator set.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_std::vec::Vec;
+
+/// How a validator has been doing in the current session.
+#[derive(Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ValidatorUptime<AccountId> {
+	/// The validator's account.
+	pub validator: AccountId,
+	/// Whether a heartbeat of the validator made it on chain in this session.
+	pub heartbeat: bool,
+	/// The number of blocks the validator authored in this session.
+	pub authored_blocks: u32,
+	/// Whether the validator is disabled for the rest of this session.
+	pub disabled: bool,
+}
+
+/// The liveness of every validator in the current session.
+#[derive(Clone, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct SessionUptime<AccountId> {
+	/// Index of the current session.
+	pub session_index: u32,
+	/// The validators of the current session, in authority index order.
+	pub validators: Vec<ValidatorUptime<AccountId>>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// API to check which validators are alive.
+	pub trait ValidatorSetApi<AccountId> where
+		AccountId: Codec,
+	{
+		/// The liveness of the current session's validators.
+		fn session_uptime() -> SessionUptime<AccountId>;
+	}
+}
@@ -0,0 +1,230 @@
+//! RPC interface for the liveness of the validator set.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+pub use pallet_validator_set_rpc_runtime_api::{
+	SessionUptime, ValidatorSetApi as ValidatorSetRuntimeApi, ValidatorUptime,
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+#[rpc]
+pub trait ValidatorSetApi<BlockHash, AccountId> {
+	/// Heartbeats, authored blocks and disabled state of each validator in the session of the
+	/// given block, or the best block.
+	#[rpc(name = "validatorSet_sessionUptime")]
+	fn session_uptime(&self, at: Option<BlockHash>) -> Result<SessionUptime<AccountId>>;
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to the runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+/// A struct that implements the [`ValidatorSetApi`].
+pub struct ValidatorSet<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> ValidatorSet<C, B> {
+	/// Create new `ValidatorSet` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block, AccountId> ValidatorSetApi<<Block as BlockT>::Hash, AccountId>
+	for ValidatorSet<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: ValidatorSetRuntimeApi<Block, AccountId>,
+	AccountId: Codec,
+{
+	fn session_uptime(
+		&self,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<SessionUptime<AccountId>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+		api.session_uptime(&at).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to query the session uptime.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use jsonrpc_core::IoHandler;
+	use sp_api::{ApiError, ApiRef, NativeOrEncoded};
+	use sp_blockchain::{BlockStatus, Info};
+	use sp_runtime::{
+		testing::{Block as TestBlock, ExtrinsicWrapper, Header},
+		traits::{Header as _, NumberFor},
+	};
+
+	type Block = TestBlock<ExtrinsicWrapper<u64>>;
+
+	/// A genesis block and its child, the best block.
+	fn chain() -> Vec<Header> {
+		let genesis = Header::new_from_number(0);
+		let best = Header { parent_hash: genesis.hash(), ..Header::new_from_number(1) };
+		vec![genesis, best]
+	}
+
+	fn find<'a>(headers: &'a [Header], id: &BlockId<Block>) -> Option<&'a Header> {
+		headers.iter().find(|header| match id {
+			BlockId::Hash(hash) => header.hash() == *hash,
+			BlockId::Number(number) => header.number == *number,
+		})
+	}
+
+	/// A client of the `chain()`, whose runtime reports the liveness of two validators, the second
+	/// one disabled, in a session numbered like the block.
+	struct TestClient {
+		headers: Vec<Header>,
+	}
+
+	struct RuntimeApi {
+		headers: Vec<Header>,
+	}
+
+	impl ProvideRuntimeApi<Block> for TestClient {
+		type Api = RuntimeApi;
+
+		fn runtime_api<'a>(&'a self) -> ApiRef<'a, Self::Api> {
+			RuntimeApi { headers: self.headers.clone() }.into()
+		}
+	}
+
+	impl HeaderBackend<Block> for TestClient {
+		fn header(
+			&self,
+			id: BlockId<Block>,
+		) -> sp_blockchain::Result<Option<<Block as BlockT>::Header>> {
+			Ok(find(&self.headers, &id).cloned())
+		}
+
+		fn info(&self) -> Info<Block> {
+			let (genesis, best) = (&self.headers[0], &self.headers[1]);
+			Info {
+				best_hash: best.hash(),
+				best_number: best.number,
+				genesis_hash: genesis.hash(),
+				finalized_hash: genesis.hash(),
+				finalized_number: genesis.number,
+				finalized_state: None,
+				number_leaves: 1,
+				block_gap: None,
+			}
+		}
+
+		fn status(&self, id: BlockId<Block>) -> sp_blockchain::Result<BlockStatus> {
+			Ok(match find(&self.headers, &id) {
+				Some(_) => BlockStatus::InChain,
+				None => BlockStatus::Unknown,
+			})
+		}
+
+		fn number(
+			&self,
+			hash: <Block as BlockT>::Hash,
+		) -> sp_blockchain::Result<Option<NumberFor<Block>>> {
+			Ok(find(&self.headers, &BlockId::Hash(hash)).map(|header| header.number))
+		}
+
+		fn hash(
+			&self,
+			number: NumberFor<Block>,
+		) -> sp_blockchain::Result<Option<<Block as BlockT>::Hash>> {
+			Ok(find(&self.headers, &BlockId::Number(number)).map(|header| header.hash()))
+		}
+	}
+
+	sp_api::mock_impl_runtime_apis! {
+		impl ValidatorSetRuntimeApi<Block, u64> for RuntimeApi {
+			#[advanced]
+			fn session_uptime(
+				&self,
+				at: &BlockId<Block>,
+			) -> std::result::Result<NativeOrEncoded<SessionUptime<u64>>, ApiError> {
+				let header = find(&self.headers, at).ok_or_else(|| {
+					let error: Box<dyn std::error::Error + Send + Sync> = "Unknown block".into();
+					ApiError::from(error)
+				})?;
+				Ok(SessionUptime {
+					session_index: header.number as u32,
+					validators: vec![
+						ValidatorUptime {
+							validator: 1,
+							heartbeat: true,
+							authored_blocks: 2,
+							disabled: false,
+						},
+						ValidatorUptime {
+							validator: 2,
+							heartbeat: false,
+							authored_blocks: 0,
+							disabled: true,
+						},
+					],
+				}
+				.into())
+			}
+		}
+	}
+
+	fn io() -> IoHandler {
+		let mut io = IoHandler::new();
+		let client = Arc::new(TestClient { headers: chain() });
+		io.extend_with(ValidatorSetApi::to_delegate(ValidatorSet::<_, Block>::new(client)));
+		io
+	}
+
+	fn request(params: &str) -> String {
+		format!(
+			r#"{{"jsonrpc":"2.0","method":"validatorSet_sessionUptime","params":{},"id":1}}"#,
+			params
+		)
+	}
+
+	const UPTIME_AT_BEST: &str = r#"{"jsonrpc":"2.0","result":{"sessionIndex":1,"validators":[{"validator":1,"heartbeat":true,"authoredBlocks":2,"disabled":false},{"validator":2,"heartbeat":false,"authoredBlocks":0,"disabled":true}]},"id":1}"#;
+
+	#[test]
+	fn session_uptime_is_served_as_camel_case_json() {
+		let best = chain()[1].hash();
+		let request = request(&format!(r#"["{:?}"]"#, best));
+		assert_eq!(io().handle_request_sync(&request), Some(UPTIME_AT_BEST.into()));
+	}
+
+	#[test]
+	fn session_uptime_defaults_to_best_block() {
+		assert_eq!(io().handle_request_sync(&request("[]")), Some(UPTIME_AT_BEST.into()));
+		assert_eq!(io().handle_request_sync(&request("[null]")), Some(UPTIME_AT_BEST.into()));
+	}
+
+	#[test]
+	fn session_uptime_at_unknown_block_is_an_error() {
+		let unknown = Header::new_from_number(7).hash();
+		let response = io().handle_request_sync(&request(&format!(r#"["{:?}"]"#, unknown)));
+		assert!(response.unwrap().contains(r#""error":{"code":1,"#));
+	}
+}
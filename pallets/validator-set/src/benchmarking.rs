@@ -0,0 +1,35 @@
+//! Benchmarking setup for pallet-validator-set
+
+use super::*;
+
+#[allow(unused)]
+use crate::Pallet as ValidatorSet;
+use frame_benchmarking::{account, benchmarks};
+
+fn fill_validators<T: Config>(count: u32) {
+	let validators = (0..count).map(|i| account("validator", i, 0)).collect::<Vec<T::AccountId>>();
+	Validators::<T>::put(validators);
+}
+
+benchmarks! {
+	add_validator {
+		// Adding scans the whole set, so benchmark it at its largest.
+		fill_validators::<T>(T::MaxValidators::get() - 1);
+		let origin = T::AddRemoveOrigin::successful_origin();
+		let who: T::AccountId = account("new", 0, 0);
+	}: _<T::Origin>(origin, who.clone())
+	verify {
+		assert!(ValidatorSet::<T>::validators().contains(&who));
+	}
+
+	remove_validator {
+		fill_validators::<T>(T::MaxValidators::get());
+		let origin = T::AddRemoveOrigin::successful_origin();
+		let who: T::AccountId = account("validator", T::MaxValidators::get() - 1, 0);
+	}: _<T::Origin>(origin, who.clone())
+	verify {
+		assert!(!ValidatorSet::<T>::validators().contains(&who));
+	}
+
+	impl_benchmark_test_suite!(ValidatorSet, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,200 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Manages the validator set of a proof-of-authority chain.
+//!
+//! Validators are added and removed by `Config::AddRemoveOrigin`. The pallet acts as the
+//! `SessionManager` of `pallet_session`, so a change is queued at the next session boundary and
+//! takes effect one session later. A new validator must register its Aura and GRANDPA keys
+//! through `Session::set_keys` before it can author or vote; validators without keys are left
+//! out of the session by `pallet_session`.
+//!
+//! The validator set is handed to `pallet_session` at every session change, even when it did not
+//! change, so that `pallet_session` also clears the validators disabled in the ending session.
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod weights;
+pub use weights::WeightInfo;
+
+use sp_staking::SessionIndex;
+use sp_std::vec::Vec;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_std::vec::Vec;
+
+	use crate::WeightInfo;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The origin allowed to add and remove validators.
+		type AddRemoveOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The validator set never shrinks below this size.
+		#[pallet::constant]
+		type MinValidators: Get<u32>;
+
+		/// The validator set never grows beyond this size. Should not exceed the `MaxAuthorities`
+		/// of Aura and GRANDPA.
+		#[pallet::constant]
+		type MaxValidators: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The accounts that will validate from the next session change on.
+	#[pallet::storage]
+	#[pallet::getter(fn validators)]
+	pub type Validators<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The validators of the first sessions. They need session keys in the genesis of
+		/// `pallet_session`.
+		pub validators: Vec<T::AccountId>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { validators: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			let mut validators = self.validators.clone();
+			validators.sort();
+			validators.dedup();
+			assert_eq!(validators.len(), self.validators.len(), "Duplicate validator in genesis.");
+			assert!(
+				validators.len() as u32 >= T::MinValidators::get(),
+				"Fewer than `MinValidators` validators in genesis."
+			);
+			assert!(
+				validators.len() as u32 <= T::MaxValidators::get(),
+				"More than `MaxValidators` validators in genesis."
+			);
+			<Validators<T>>::put(&self.validators);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A validator was added. It joins at a coming session change. [who]
+		ValidatorAdded(T::AccountId),
+		/// A validator was removed. It leaves at a coming session change. [who]
+		ValidatorRemoved(T::AccountId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account is already a validator.
+		AlreadyValidator,
+		/// The account is not a validator.
+		NotValidator,
+		/// Adding a validator would exceed `MaxValidators`.
+		TooManyValidators,
+		/// Removing a validator would go below `MinValidators`.
+		TooFewValidators,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Add `who` to the validator set from the next session change on.
+		#[pallet::weight(T::WeightInfo::add_validator())]
+		pub fn add_validator(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			let mut validators = <Validators<T>>::get();
+			ensure!(!validators.contains(&who), Error::<T>::AlreadyValidator);
+			ensure!(
+				(validators.len() as u32) < T::MaxValidators::get(),
+				Error::<T>::TooManyValidators
+			);
+
+			validators.push(who.clone());
+			<Validators<T>>::put(validators);
+
+			Self::deposit_event(Event::ValidatorAdded(who));
+			Ok(())
+		}
+
+		/// Remove `who` from the validator set from the next session change on.
+		#[pallet::weight(T::WeightInfo::remove_validator())]
+		pub fn remove_validator(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			let mut validators = <Validators<T>>::get();
+			let index =
+				validators.iter().position(|v| v == &who).ok_or(Error::<T>::NotValidator)?;
+			ensure!(
+				validators.len() as u32 > T::MinValidators::get(),
+				Error::<T>::TooFewValidators
+			);
+
+			validators.remove(index);
+			<Validators<T>>::put(validators);
+
+			Self::deposit_event(Event::ValidatorRemoved(who));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> pallet_session::SessionManager<T::AccountId> for Pallet<T> {
+	fn new_session(_new_index: SessionIndex) -> Option<Vec<T::AccountId>> {
+		Some(<Validators<T>>::get())
+	}
+
+	fn new_session_genesis(_new_index: SessionIndex) -> Option<Vec<T::AccountId>> {
+		Some(<Validators<T>>::get())
+	}
+
+	fn start_session(_start_index: SessionIndex) {}
+
+	fn end_session(_end_index: SessionIndex) {}
+}
+
+/// Validators are not bonded, so the historical pallet identifies each of them by `()` alone.
+impl<T: Config> pallet_session::historical::SessionManager<T::AccountId, ()> for Pallet<T> {
+	fn new_session(new_index: SessionIndex) -> Option<Vec<(T::AccountId, ())>> {
+		<Self as pallet_session::SessionManager<_>>::new_session(new_index)
+			.map(|validators| validators.into_iter().map(|v| (v, ())).collect())
+	}
+
+	fn new_session_genesis(new_index: SessionIndex) -> Option<Vec<(T::AccountId, ())>> {
+		<Self as pallet_session::SessionManager<_>>::new_session_genesis(new_index)
+			.map(|validators| validators.into_iter().map(|v| (v, ())).collect())
+	}
+
+	fn start_session(start_index: SessionIndex) {
+		<Self as pallet_session::SessionManager<_>>::start_session(start_index)
+	}
+
+	fn end_session(end_index: SessionIndex) {
+		<Self as pallet_session::SessionManager<_>>::end_session(end_index)
+	}
+}
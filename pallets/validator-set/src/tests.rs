@@ -0,0 +1,80 @@
+use crate::{mock::*, Error, Event as ValidatorSetEvent};
+use frame_support::{assert_noop, assert_ok};
+use pallet_session::SessionManager;
+use sp_runtime::DispatchError;
+
+fn last_event() -> ValidatorSetEvent<Test> {
+	System::events()
+		.into_iter()
+		.filter_map(|r| if let Event::ValidatorSet(e) = r.event { Some(e) } else { None })
+		.last()
+		.unwrap()
+}
+
+#[test]
+fn genesis_validators_start_the_first_sessions() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(ValidatorSet::validators(), vec![1, 2, 3]);
+		assert_eq!(ValidatorSet::new_session_genesis(0), Some(vec![1, 2, 3]));
+		// The set is handed over unchanged, so that the session pallet clears disabled validators.
+		assert_eq!(ValidatorSet::new_session(2), Some(vec![1, 2, 3]));
+	});
+}
+
+#[test]
+fn added_validator_joins_at_next_session() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValidatorSet::add_validator(Origin::root(), 4));
+		assert_eq!(last_event(), ValidatorSetEvent::ValidatorAdded(4));
+
+		assert_eq!(ValidatorSet::new_session(2), Some(vec![1, 2, 3, 4]));
+		assert_eq!(ValidatorSet::new_session(3), Some(vec![1, 2, 3, 4]));
+	});
+}
+
+#[test]
+fn removed_validator_leaves_at_next_session() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 2));
+		assert_eq!(last_event(), ValidatorSetEvent::ValidatorRemoved(2));
+
+		assert_eq!(ValidatorSet::new_session(2), Some(vec![1, 3]));
+	});
+}
+
+#[test]
+fn only_add_remove_origin_changes_validators() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ValidatorSet::add_validator(Origin::signed(1), 4), DispatchError::BadOrigin);
+		assert_noop!(
+			ValidatorSet::remove_validator(Origin::signed(1), 2),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn validator_set_stays_within_bounds() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			ValidatorSet::add_validator(Origin::root(), 1),
+			Error::<Test>::AlreadyValidator
+		);
+		assert_ok!(ValidatorSet::add_validator(Origin::root(), 4));
+		assert_noop!(
+			ValidatorSet::add_validator(Origin::root(), 5),
+			Error::<Test>::TooManyValidators
+		);
+
+		assert_noop!(
+			ValidatorSet::remove_validator(Origin::root(), 5),
+			Error::<Test>::NotValidator
+		);
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 4));
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 3));
+		assert_noop!(
+			ValidatorSet::remove_validator(Origin::root(), 2),
+			Error::<Test>::TooFewValidators
+		);
+	});
+}
@@ -0,0 +1,51 @@
+//! Runtime-level storage migrations, run by `Executive` before those of the pallets.
+
+use crate::{AccountId, Council, Runtime};
+use frame_support::{
+	storage::{migration, unhashed},
+	traits::{ChangeMembers, Get, OnRuntimeUpgrade},
+	weights::Weight,
+	StorageHasher, Twox128,
+};
+
+/// The migrations to run on the next runtime upgrade.
+pub type Migrations = (SudoToCouncil,);
+
+/// Name under which `pallet_sudo` was part of `construct_runtime!`.
+const SUDO: &[u8] = b"Sudo";
+
+/// Hands the powers of the removed `pallet_sudo` over to governance.
+///
+/// If the council has no members yet, the former sudo key becomes its only member, so that it
+/// can bring in the other members through a motion and a referendum. Any storage left behind by
+/// `pallet_sudo` is removed.
+pub struct SudoToCouncil;
+
+impl OnRuntimeUpgrade for SudoToCouncil {
+	fn on_runtime_upgrade() -> Weight {
+		let db = <Runtime as frame_system::Config>::DbWeight::get();
+		let key = match migration::get_storage_value::<AccountId>(SUDO, b"Key", &[]) {
+			Some(key) => key,
+			None => return db.reads(1),
+		};
+
+		let mut weight = db.reads_writes(2, 1);
+		if Council::members().is_empty() {
+			<Council as ChangeMembers<AccountId>>::set_members_sorted(&[key], &[]);
+			weight = weight.saturating_add(db.writes(2));
+		}
+		let _ = unhashed::kill_prefix(&Twox128::hash(SUDO), None);
+
+		weight
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			migration::get_storage_value::<AccountId>(SUDO, b"Key", &[]).is_none(),
+			"Sudo key still in storage"
+		);
+		frame_support::ensure!(!Council::members().is_empty(), "Council has no members");
+		Ok(())
+	}
+}
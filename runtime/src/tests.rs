@@ -0,0 +1,437 @@
+use crate::{migrations::SudoToCouncil, *};
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::migration,
+	traits::{
+		ChangeMembers, Contains, Currency, Get, InstanceFilter, OnInitialize, OnRuntimeUpgrade,
+		OnUnbalanced,
+	},
+	weights::DispatchClass,
+};
+use sp_consensus_aura::{Slot, AURA_ENGINE_ID};
+use sp_core::{ed25519, sr25519};
+use sp_runtime::DigestItem;
+use sp_staking::offence::ReportOffence;
+
+fn validator(seed: u8) -> (AccountId, opaque::SessionKeys) {
+	let keys = opaque::SessionKeys {
+		aura: sr25519::Public::from_raw([seed; 32]).into(),
+		grandpa: ed25519::Public::from_raw([seed; 32]).into(),
+		im_online: sr25519::Public::from_raw([seed; 32]).into(),
+	};
+	(AccountId::from([seed; 32]), keys)
+}
+
+/// Genesis of a chain whose validators are built from `seeds`, in that order.
+fn genesis(seeds: &[u8]) -> GenesisConfig {
+	let validators = seeds.iter().map(|seed| validator(*seed)).collect::<Vec<_>>();
+	GenesisConfig {
+		balances: BalancesConfig {
+			balances: validators.iter().map(|(who, _)| (who.clone(), 1 << 60)).collect(),
+		},
+		validator_set: ValidatorSetConfig {
+			validators: validators.iter().map(|(who, _)| who.clone()).collect(),
+		},
+		session: SessionConfig {
+			keys: validators.into_iter().map(|(who, keys)| (who.clone(), who, keys)).collect(),
+		},
+		..Default::default()
+	}
+}
+
+/// Externalities of a chain whose validators are built from `seeds`, in that order.
+fn new_test_ext(seeds: &[u8]) -> sp_io::TestExternalities {
+	genesis(seeds).build_storage().unwrap().into()
+}
+
+/// Start block 1 as if authored in `slot`.
+fn initialize_block_in_slot(slot: u64) {
+	System::initialize(&1, &Default::default(), &Default::default());
+	System::deposit_log(DigestItem::PreRuntime(AURA_ENGINE_ID, Slot::from(slot).encode()));
+	Aura::on_initialize(1);
+}
+
+#[test]
+fn aura_authorities_come_from_session() {
+	new_test_ext(&[1, 2]).execute_with(|| {
+		let aura = |seed| validator(seed).1.aura;
+		assert_eq!(Aura::authorities().into_inner(), vec![aura(1), aura(2)]);
+	});
+}
+
+#[test]
+fn aura_accepts_slots_of_enabled_authorities() {
+	new_test_ext(&[1, 2]).execute_with(|| {
+		assert!(Session::disable(&validator(1).0));
+
+		// Slot 3 belongs to the second authority, which is still enabled.
+		initialize_block_in_slot(3);
+		assert_eq!(Aura::current_slot(), Slot::from(3));
+	});
+}
+
+#[test]
+#[should_panic(expected = "is disabled and should not be attempting to author blocks")]
+fn aura_skips_slots_of_disabled_authorities() {
+	new_test_ext(&[1, 2]).execute_with(|| {
+		assert!(Session::disable(&validator(1).0));
+
+		// Slot 4 belongs to the first authority, so no block may be authored in it.
+		initialize_block_in_slot(4);
+	});
+}
+
+/// Report a GRANDPA equivocation of `offender` in the current session.
+fn report_equivocation(offender: AccountId) {
+	let offence = pallet_grandpa::GrandpaEquivocationOffence {
+		time_slot: pallet_grandpa::GrandpaTimeSlot { set_id: 0, round: 1 },
+		session_index: Session::current_index(),
+		validator_set_count: Session::validators().len() as u32,
+		offender: (offender, ()),
+	};
+	assert_ok!(Offences::report_offence(vec![], offence));
+}
+
+#[test]
+fn offences_disable_as_their_strategy_asks() {
+	new_test_ext(&[1, 2]).execute_with(|| {
+		let offender =
+			|seed| OffenceDetails { offender: (validator(seed).0, ()), reporters: vec![] };
+		let (slashed, unslashed) = ([Perbill::from_percent(10)], [Perbill::from_percent(0)]);
+
+		DisableOffenders::on_offence(&[offender(1)], &slashed, 0, DisableStrategy::Never);
+		DisableOffenders::on_offence(&[offender(1)], &unslashed, 0, DisableStrategy::WhenSlashed);
+		assert!(Session::disabled_validators().is_empty());
+
+		DisableOffenders::on_offence(&[offender(1)], &slashed, 0, DisableStrategy::WhenSlashed);
+		DisableOffenders::on_offence(&[offender(2)], &unslashed, 0, DisableStrategy::Always);
+		assert_eq!(Session::disabled_validators(), vec![0, 1]);
+	});
+}
+
+#[test]
+fn equivocating_validator_authors_again_after_session_change() {
+	new_test_ext(&[1, 2]).execute_with(|| {
+		// The first session change after genesis keeps the disabled validators.
+		Session::rotate_session();
+
+		report_equivocation(validator(1).0);
+		assert_eq!(Session::disabled_validators(), vec![0]);
+
+		Session::rotate_session();
+		assert!(Session::disabled_validators().is_empty());
+		// Slot 4 belongs to the first authority, which got its slots back.
+		initialize_block_in_slot(4);
+		assert_eq!(Aura::current_slot(), Slot::from(4));
+	});
+}
+
+#[test]
+fn disabled_authority_gets_its_slots_back_next_session() {
+	new_test_ext(&[1, 2]).execute_with(|| {
+		// The first session change after genesis keeps the disabled validators.
+		Session::rotate_session();
+		assert!(Session::disable(&validator(1).0));
+
+		Session::rotate_session();
+		// Slot 4 belongs to the first authority.
+		initialize_block_in_slot(4);
+		assert_eq!(Aura::current_slot(), Slot::from(4));
+	});
+}
+
+#[test]
+fn session_uptime_reports_authored_blocks_and_disabled_validators() {
+	use pallet_validator_set_rpc_runtime_api::runtime_decl_for_ValidatorSetApi::ValidatorSetApi;
+
+	new_test_ext(&[1, 2]).execute_with(|| {
+		<ImOnline as pallet_authorship::EventHandler<_, _>>::note_author(validator(1).0);
+		<ImOnline as pallet_authorship::EventHandler<_, _>>::note_author(validator(1).0);
+		assert!(Session::disable(&validator(2).0));
+
+		let uptime = <Runtime as ValidatorSetApi<Block, AccountId>>::session_uptime();
+		assert_eq!(uptime.session_index, 0);
+		assert_eq!(
+			uptime.validators,
+			vec![
+				ValidatorUptime {
+					validator: validator(1).0,
+					heartbeat: false,
+					authored_blocks: 2,
+					disabled: false,
+				},
+				ValidatorUptime {
+					validator: validator(2).0,
+					heartbeat: false,
+					authored_blocks: 0,
+					disabled: true,
+				},
+			]
+		);
+	});
+}
+
+fn put_sudo_key(key: &AccountId) {
+	migration::put_storage_value(b"Sudo", b"Key", &[], key);
+}
+
+fn sudo_key() -> Option<AccountId> {
+	migration::get_storage_value(b"Sudo", b"Key", &[])
+}
+
+#[test]
+fn sudo_key_joins_empty_council() {
+	new_test_ext(&[1]).execute_with(|| {
+		let key = AccountId::from([9; 32]);
+		put_sudo_key(&key);
+
+		SudoToCouncil::on_runtime_upgrade();
+
+		assert_eq!(Council::members(), vec![key]);
+		assert_eq!(sudo_key(), None);
+	});
+}
+
+#[test]
+fn sudo_key_is_dropped_when_council_exists() {
+	new_test_ext(&[1]).execute_with(|| {
+		let member = AccountId::from([8; 32]);
+		<Council as ChangeMembers<AccountId>>::set_members_sorted(&[member.clone()], &[]);
+		put_sudo_key(&AccountId::from([9; 32]));
+
+		SudoToCouncil::on_runtime_upgrade();
+
+		assert_eq!(Council::members(), vec![member]);
+		assert_eq!(sudo_key(), None);
+	});
+}
+
+#[test]
+fn migration_without_sudo_is_noop() {
+	new_test_ext(&[1]).execute_with(|| {
+		SudoToCouncil::on_runtime_upgrade();
+
+		assert!(Council::members().is_empty());
+	});
+}
+
+#[test]
+fn template_proxy_only_makes_template_calls() {
+	let template = Call::TemplateModule(pallet_template::Call::set_value { key: 0, value: 42 });
+	let transfer = Call::Balances(pallet_balances::Call::transfer {
+		dest: validator(2).0.into(),
+		value: 1_000,
+	});
+	let batch = Call::Utility(pallet_utility::Call::batch { calls: vec![template.clone()] });
+
+	assert!(ProxyType::TemplateModule.filter(&template));
+	assert!(ProxyType::TemplateModule.filter(&batch));
+	assert!(!ProxyType::TemplateModule.filter(&transfer));
+	assert!(ProxyType::Any.filter(&transfer));
+}
+
+#[test]
+fn non_transfer_proxy_cannot_move_funds() {
+	let transfer_value = Call::TemplateModule(pallet_template::Call::transfer_value {
+		key: 0,
+		dest: validator(2).0,
+	});
+	let set_value = Call::TemplateModule(pallet_template::Call::set_value { key: 0, value: 42 });
+
+	assert!(!ProxyType::NonTransfer.filter(&transfer_value));
+	assert!(ProxyType::NonTransfer.filter(&set_value));
+	assert!(ProxyType::NonTransfer.is_superset(&ProxyType::Governance));
+	assert!(!ProxyType::TemplateModule.is_superset(&ProxyType::NonTransfer));
+}
+
+type FeeMultiplierUpdate = <Runtime as pallet_transaction_payment::Config>::FeeMultiplierUpdate;
+
+/// The normal-class weight of a block that is full.
+fn full_block() -> Weight {
+	BlockWeights::get().get(DispatchClass::Normal).max_total.unwrap()
+}
+
+/// The fee multiplier after `blocks` blocks that each used `weight`, starting from `multiplier`.
+fn run_blocks(weight: Weight, blocks: u32, multiplier: Multiplier) -> Multiplier {
+	(0..blocks).fold(multiplier, |multiplier, _| {
+		System::set_block_consumed_resources(weight, 0);
+		FeeMultiplierUpdate::convert(multiplier)
+	})
+}
+
+#[test]
+fn full_blocks_raise_the_fee_multiplier() {
+	new_test_ext(&[1]).execute_with(|| {
+		let mut multiplier = MinimumMultiplier::get();
+		for _ in 0..10 {
+			let next = run_blocks(full_block(), 100, multiplier);
+			assert!(next > multiplier);
+			multiplier = next;
+		}
+		// A thousand full blocks about double the fees.
+		assert!(multiplier > Multiplier::saturating_from_integer(2));
+		assert!(multiplier < Multiplier::saturating_from_integer(3));
+	});
+}
+
+#[test]
+fn empty_blocks_lower_the_fee_multiplier_to_the_minimum() {
+	new_test_ext(&[1]).execute_with(|| {
+		let congested = Multiplier::saturating_from_integer(2);
+		let multiplier = run_blocks(0, 100, congested);
+		assert!(multiplier < congested);
+		assert!(multiplier > MinimumMultiplier::get());
+
+		assert_eq!(run_blocks(0, 10_000, multiplier), MinimumMultiplier::get());
+	});
+}
+
+#[test]
+fn blocks_at_target_fullness_keep_the_fee_multiplier() {
+	new_test_ext(&[1]).execute_with(|| {
+		let target = TargetBlockFullness::get() * full_block();
+		let multiplier = Multiplier::saturating_from_integer(2);
+		assert_eq!(run_blocks(target, 100, multiplier), multiplier);
+	});
+}
+
+#[test]
+fn fee_multiplier_recovers_after_congestion() {
+	new_test_ext(&[1]).execute_with(|| {
+		let congested = run_blocks(full_block(), 1_000, MinimumMultiplier::get());
+		assert!(congested > MinimumMultiplier::get());
+
+		let recovered = run_blocks(0, 1_000, congested);
+		assert!(recovered < congested);
+		assert_eq!(run_blocks(0, 10_000, recovered), MinimumMultiplier::get());
+	});
+}
+
+#[test]
+fn weight_to_fee_grows_faster_than_weight() {
+	let base = ExtrinsicBaseWeight::get();
+	let fee = |weight: Weight| WeightToFee::calc(&weight);
+
+	assert_eq!(fee(0), 0);
+	assert_eq!(fee(1_000_000_000), 2_000_000_000);
+	assert!(fee(2 * base) > 2 * fee(base));
+	assert!(fee(full_block()) > 1_000 * full_block() as Balance);
+}
+
+#[test]
+fn fees_are_split_between_treasury_and_author() {
+	new_test_ext(&[1, 2]).execute_with(|| {
+		// Slot 3 belongs to the second validator.
+		initialize_block_in_slot(3);
+		let author = validator(2).0;
+		let treasury = Treasury::account_id();
+		let author_balance = Balances::free_balance(&author);
+		let treasury_balance = Balances::free_balance(&treasury);
+
+		let fees = Balances::issue(1_000);
+		let tips = Balances::issue(300);
+		DealWithFees::on_unbalanceds(vec![fees, tips].into_iter());
+
+		assert_eq!(Balances::free_balance(&treasury), treasury_balance + 800);
+		assert_eq!(Balances::free_balance(&author), author_balance + 200 + 300);
+	});
+}
+
+#[test]
+fn dust_goes_to_treasury() {
+	new_test_ext(&[1, 2]).execute_with(|| {
+		let treasury = Treasury::account_id();
+		let treasury_balance = Balances::free_balance(&treasury);
+		let (from, to) = (AccountId::from([9; 32]), validator(2).0);
+		let dust = ExistentialDeposit::get() - 1;
+		Balances::make_free_balance_be(&from, 10_000);
+
+		assert_ok!(Balances::transfer(Origin::signed(from.clone()), to.into(), 10_000 - dust));
+
+		assert_eq!(Balances::free_balance(&from), 0);
+		assert_eq!(Balances::free_balance(&treasury), treasury_balance + dust);
+	});
+}
+
+type BaseFilter = <Runtime as frame_system::Config>::BaseCallFilter;
+
+/// The origin of a motion passed unanimously by a one-member council.
+fn council() -> Origin {
+	pallet_collective::RawOrigin::<AccountId, CouncilCollective>::Members(1, 1).into()
+}
+
+#[test]
+fn paused_template_call_is_filtered() {
+	new_test_ext(&[1]).execute_with(|| {
+		let do_something =
+			Call::TemplateModule(pallet_template::Call::do_something { something: 1 });
+		let set_value =
+			Call::TemplateModule(pallet_template::Call::set_value { key: 0, value: 42 });
+
+		assert_ok!(TransactionPause::pause(
+			council(),
+			b"TemplateModule".to_vec(),
+			Some(b"do_something".to_vec())
+		));
+
+		assert!(!BaseFilter::contains(&do_something));
+		assert!(BaseFilter::contains(&set_value));
+	});
+}
+
+#[test]
+fn timestamp_cannot_be_paused() {
+	new_test_ext(&[1]).execute_with(|| {
+		assert_noop!(
+			TransactionPause::pause(council(), b"Timestamp".to_vec(), Some(b"set".to_vec())),
+			pallet_transaction_pause::Error::<Runtime>::Unpausable
+		);
+		assert!(BaseFilter::contains(&Call::Timestamp(pallet_timestamp::Call::set { now: 1 })));
+	});
+}
+
+#[test]
+fn system_consensus_and_governance_cannot_be_paused() {
+	new_test_ext(&[1]).execute_with(|| {
+		for pallet_name in [
+			"System",
+			"Timestamp",
+			"Authorship",
+			"Grandpa",
+			"ImOnline",
+			"Council",
+			"Democracy",
+			"TransactionPause",
+		] {
+			assert_noop!(
+				TransactionPause::pause(council(), pallet_name.as_bytes().to_vec(), None),
+				pallet_transaction_pause::Error::<Runtime>::Unpausable
+			);
+		}
+	});
+}
+
+#[test]
+fn vested_transfers_create_accounts() {
+	assert!(MinVestedTransfer::get() >= ExistentialDeposit::get());
+}
+
+#[test]
+fn genesis_vesting_unlocks_linearly() {
+	let who = AccountId::from([9; 32]);
+	let mut genesis = genesis(&[1]);
+	genesis.balances.balances.push((who.clone(), 10_000));
+	// 8_000 vest over 100 blocks from block 10 on.
+	genesis.vesting.vesting.push((who.clone(), 10, 100, 2_000));
+	let mut ext: sp_io::TestExternalities = genesis.build_storage().unwrap().into();
+
+	ext.execute_with(|| {
+		assert_eq!(Vesting::vesting_balance(&who), Some(8_000));
+
+		System::set_block_number(60);
+		assert_eq!(Vesting::vesting_balance(&who), Some(4_000));
+
+		System::set_block_number(110);
+		assert_eq!(Vesting::vesting_balance(&who), Some(0));
+	});
+}